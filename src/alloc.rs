@@ -0,0 +1,104 @@
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::cell::Cell;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+//Structs
+
+//a global allocator that counts each thread's allocations. install it with
+//#[global_allocator] (the binary does with the alloc-stats feature) to get memory stats per phase
+pub struct Counting;
+
+//bytes allocated during a phase: the most held at once above what was held when it started, and the sum of all allocations
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Memory {
+    pub peak: usize,
+    pub total: usize,
+}
+
+//counters at the start of a phase
+pub struct Measure {
+    current: usize,
+    total: usize,
+}
+
+impl Measure {
+    //start measuring a phase on this thread
+    pub fn start() -> Self {
+        let current = CURRENT.with(|c| c.get());
+        PEAK.with(|p| p.set(current));
+
+        Self {
+            current,
+            total: TOTAL.with(|t| t.get()),
+        }
+    }
+
+    //what the phase allocated, or none if allocations aren't being counted
+    pub fn finish(self) -> Option<Memory> {
+        if !enabled() {
+            return None;
+        }
+
+        Some(Memory {
+            peak: PEAK.with(|p| p.get()).saturating_sub(self.current),
+            total: TOTAL.with(|t| t.get()) - self.total,
+        })
+    }
+}
+
+//Statics
+
+static INSTALLED: AtomicBool = AtomicBool::new(false);
+
+//counted per thread, so days running on other threads don't show up in each other's phases
+thread_local! {
+    static CURRENT: Cell<usize> = const { Cell::new(0) };
+    static PEAK: Cell<usize> = const { Cell::new(0) };
+    static TOTAL: Cell<usize> = const { Cell::new(0) };
+}
+
+fn on_alloc(size: usize) {
+    //counters can't be touched while the thread is being torn down
+    let _ = CURRENT.try_with(|c| {
+        let now = c.get() + size;
+        c.set(now);
+        let _ = PEAK.try_with(|p| p.set(p.get().max(now)));
+        let _ = TOTAL.try_with(|t| t.set(t.get() + size));
+    });
+}
+
+fn on_dealloc(size: usize) {
+    let _ = CURRENT.try_with(|c| c.set(c.get().saturating_sub(size)));
+}
+
+unsafe impl GlobalAlloc for Counting {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        INSTALLED.store(true, Ordering::Relaxed);
+        on_alloc(layout.size());
+        System.alloc(layout)
+    }
+
+    unsafe fn alloc_zeroed(&self, layout: Layout) -> *mut u8 {
+        INSTALLED.store(true, Ordering::Relaxed);
+        on_alloc(layout.size());
+        System.alloc_zeroed(layout)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        on_dealloc(layout.size());
+        System.dealloc(ptr, layout)
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        on_dealloc(layout.size());
+        on_alloc(new_size);
+        System.realloc(ptr, layout, new_size)
+    }
+}
+
+//Functions
+
+//whether the counting allocator is in use
+pub fn enabled() -> bool {
+    INSTALLED.load(Ordering::Relaxed)
+}
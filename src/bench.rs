@@ -0,0 +1,146 @@
+use crate::output::{fmt_time, table};
+use crate::runner::{no_solution, Inputs};
+use crate::solutions::DAYS;
+use crate::solve;
+use crate::utils::{Answer, SolveError};
+use std::time::Duration;
+
+//Structs
+
+//summary statistics over the timings of one phase
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Stats {
+    pub min: Duration,
+    pub median: Duration,
+    pub mean: Duration,
+    pub stddev: Duration,
+}
+
+impl Stats {
+    pub fn from(ts: &[Duration]) -> Option<Self> {
+        if ts.is_empty() {
+            return None;
+        }
+
+        let mut sorted = ts.to_vec();
+        sorted.sort();
+
+        let n = ts.len() as f64;
+        let secs: Vec<f64> = ts.iter().map(|t| t.as_secs_f64()).collect();
+        let mean = secs.iter().sum::<f64>() / n;
+        //sample standard deviation, or zero for a single run
+        let var = if ts.len() > 1 {
+            secs.iter().map(|t| (t - mean).powi(2)).sum::<f64>() / (n - 1.0)
+        } else {
+            0.0
+        };
+
+        let mid = sorted.len() / 2;
+        let median = if sorted.len().is_multiple_of(2) {
+            (sorted[mid - 1] + sorted[mid]) / 2
+        } else {
+            sorted[mid]
+        };
+
+        Some(Stats {
+            min: sorted[0],
+            median,
+            mean: Duration::from_secs_f64(mean),
+            stddev: Duration::from_secs_f64(var.sqrt()),
+        })
+    }
+}
+
+//per-phase statistics for one day; a phase is none if it was never timed
+pub struct Bench {
+    pub day: usize,
+    pub runs: usize,
+    pub parse: Option<Stats>,
+    pub part1: Option<Stats>,
+    pub part2: Option<Stats>,
+    pub overall: Option<Stats>,
+}
+
+//Functions
+
+//run a day runs times after some untimed warm-up runs, collecting statistics for each phase
+pub fn bench_day(day: usize, input: &str, runs: usize, warmup: usize) -> Result<Bench, SolveError> {
+    let run = || match solve(day, input.to_string()) {
+        Some(res) => res,
+        None => Err(no_solution(day)),
+    };
+
+    for _ in 0..warmup {
+        run()?;
+    }
+
+    let answers: Vec<Answer> = (0..runs).map(|_| run()).collect::<Result<_, _>>()?;
+    let phase = |f: fn(&Answer) -> Option<Duration>| {
+        Stats::from(&answers.iter().filter_map(f).collect::<Vec<_>>())
+    };
+
+    Ok(Bench {
+        day,
+        runs,
+        parse: phase(Answer::parse_time),
+        part1: phase(Answer::part1_time),
+        part2: phase(Answer::part2_time),
+        overall: phase(|a| Some(a.overall_time())),
+    })
+}
+
+//bench each day against its own input
+pub fn bench_days(
+    days: &[usize],
+    inputs: &Inputs,
+    runs: usize,
+    warmup: usize,
+) -> Vec<Result<Bench, SolveError>> {
+    days.iter()
+        .map(|d| match *d > DAYS.len() {
+            true => Err(no_solution(*d)),
+            false => bench_day(*d, &inputs.read(*d)?, runs, warmup),
+        })
+        .collect()
+}
+
+pub fn report(benches: &[Result<Bench, SolveError>]) -> String {
+    let header = ["Day", "Phase", "Min", "Median", "Mean", "Stddev"];
+    let mut rows = vec![];
+
+    for b in benches {
+        let b = match b {
+            Ok(b) => b,
+            Err(e) => {
+                rows.push(vec![e.day.unwrap_or(0).to_string(), e.to_string()]);
+                continue;
+            }
+        };
+
+        for (name, s) in [
+            ("Parse", b.parse),
+            ("Part 1", b.part1),
+            ("Part 2", b.part2),
+            ("Overall", b.overall),
+        ] {
+            rows.push(match s {
+                Some(s) => vec![
+                    b.day.to_string(),
+                    name.to_string(),
+                    fmt_time(Some(s.min)),
+                    fmt_time(Some(s.median)),
+                    fmt_time(Some(s.mean)),
+                    fmt_time(Some(s.stddev)),
+                ],
+                None => vec![b.day.to_string(), name.to_string(), fmt_time(None)],
+            });
+        }
+    }
+
+    let runs = benches.iter().flatten().map(|b| b.runs).next().unwrap_or(0);
+    format!(
+        "{}\n{} timed runs per day",
+        table(&header, &rows, &[1]),
+        runs
+    )
+}
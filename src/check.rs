@@ -0,0 +1,181 @@
+use crate::runner::{DayResult, Inputs};
+use crate::utils::{Phase, SolveError};
+use std::collections::HashMap;
+use std::fmt::{Display, Formatter};
+use std::fs;
+
+//Types
+
+//known-correct answers keyed by (day, part)
+pub type Expected = HashMap<(usize, usize), String>;
+
+#[derive(Debug, PartialEq)]
+pub enum Status {
+    Pass,
+    Fail { expected: String, got: String },
+    Missing(String),
+    Error(SolveError),
+}
+
+impl Display for Status {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Status::Pass => write!(f, "PASS"),
+            Status::Fail { expected, got } => {
+                write!(f, "FAIL (expected {}, got {})", expected, got)
+            }
+            Status::Missing(why) => write!(f, "MISSING ({})", why),
+            Status::Error(e) => write!(f, "FAIL ({})", e),
+        }
+    }
+}
+
+//Functions
+
+//answers live beside the inputs they're for: answers.txt, or answers.<name>.txt for named inputs
+pub fn answers_path(inputs: &Inputs) -> String {
+    let dir = inputs.dir.trim_end_matches('/');
+    match &inputs.name {
+        None => format!("{}/answers.txt", dir),
+        Some(n) => format!("{}/answers.{}.txt", dir, n),
+    }
+}
+
+//parse an answer store: one `<day> <part> <answer>` per line, with # comments
+pub fn parse_expected(input: &str) -> Result<Expected, String> {
+    let mut expected = HashMap::new();
+
+    for (n, l) in input.lines().enumerate() {
+        let l = l.trim();
+        if l.is_empty() || l.starts_with('#') {
+            continue;
+        }
+
+        let mut sp = l.splitn(3, char::is_whitespace);
+        match (
+            sp.next().map(str::parse::<usize>),
+            sp.next().map(str::parse::<usize>),
+            sp.next().map(str::trim),
+        ) {
+            (Some(Ok(d)), Some(Ok(p @ (1 | 2))), Some(a)) if !a.is_empty() => {
+                expected.insert((d, p), a.to_string());
+            }
+            _ => {
+                return Err(format!(
+                    "Invalid answer on line {}: expected `<day> <part> <answer>`, found `{}`",
+                    n + 1,
+                    l
+                ))
+            }
+        }
+    }
+
+    Ok(expected)
+}
+
+pub fn load_expected(path: &str) -> Result<Expected, String> {
+    match fs::read_to_string(path) {
+        Ok(s) => parse_expected(&s),
+        Err(_) => Err(format!("Could not open answer file {}", path)),
+    }
+}
+
+pub fn status(expected: Option<&String>, got: Option<&str>) -> Status {
+    match (expected, got.map(str::trim)) {
+        (None, _) => Status::Missing(String::from("no expected answer")),
+        (_, None) => Status::Missing(String::from("not implemented")),
+        (Some(e), Some(g)) if e == g => Status::Pass,
+        (Some(e), Some(g)) => Status::Fail {
+            expected: e.clone(),
+            got: g.to_string(),
+        },
+    }
+}
+
+//compare each day's parts against the store, returning a report and whether nothing failed
+pub fn check(results: &[DayResult], expected: &Expected) -> (String, bool) {
+    let mut lines = vec![];
+    let mut ok = true;
+
+    for (day, res) in results {
+        for part in 1..3 {
+            let s = match res {
+                Ok(a) => status(
+                    expected.get(&(*day, part)),
+                    if part == 1 { a.part1() } else { a.part2() },
+                ),
+                Err(e) if e.phase == Phase::Input => Status::Missing(e.msg.clone()),
+                Err(e) => Status::Error(e.clone()),
+            };
+            ok &= !matches!(s, Status::Fail { .. } | Status::Error(_));
+            lines.push(format!("Day {:>2} Part {}: {}", day, part, s));
+        }
+    }
+
+    (lines.join("\n"), ok)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::runner::no_solution;
+    use crate::utils::Answer;
+
+    fn answer(part1: &str, part2: Option<&str>) -> Answer {
+        let mut a = Answer::default();
+        a.record("Part 1", &part1);
+        if let Some(p) = part2 {
+            a.record("Part 2", &p);
+        }
+        a
+    }
+
+    #[test]
+    fn expected_answers() {
+        let expected = parse_expected("# day 1\n1 1 7\n\n1 2  1,2,3 \n").unwrap();
+        assert_eq!(expected.get(&(1, 1)).map(String::as_str), Some("7"));
+        assert_eq!(expected.get(&(1, 2)).map(String::as_str), Some("1,2,3"));
+
+        for bad in ["1 3 7", "x 1 7", "1 1", "1"] {
+            assert_eq!(
+                parse_expected(&format!("1 1 7\n{}", bad)),
+                Err(format!(
+                    "Invalid answer on line 2: expected `<day> <part> <answer>`, found `{}`",
+                    bad
+                ))
+            );
+        }
+    }
+
+    #[test]
+    fn statuses() {
+        let expected = parse_expected("1 1 7\n1 2 8\n2 1 5\n3 1 1\n3 2 2").unwrap();
+        let results: Vec<DayResult> = vec![
+            (1, Ok(answer("7", Some("9")))),
+            (2, Ok(answer(" 5\n", None))),
+            (3, Err(SolveError::new(Phase::Part1, "boom").on_day(3))),
+            (23, Err(no_solution(23))),
+        ];
+
+        let (report, ok) = check(&results, &expected);
+        assert!(!ok);
+        assert_eq!(
+            report,
+            "Day  1 Part 1: PASS
+Day  1 Part 2: FAIL (expected 8, got 9)
+Day  2 Part 1: PASS
+Day  2 Part 2: MISSING (no expected answer)
+Day  3 Part 1: FAIL (Day 3 part 1 error: boom)
+Day  3 Part 2: FAIL (Day 3 part 1 error: boom)
+Day 23 Part 1: MISSING (No solution for that day yet.)
+Day 23 Part 2: MISSING (No solution for that day yet.)"
+        );
+
+        //missing answers alone don't fail the check
+        assert!(check(&results[1..2], &expected).1);
+        assert_eq!(
+            status(Some(&String::from("5")), None),
+            Status::Missing(String::from("not implemented"))
+        );
+    }
+}
@@ -0,0 +1,335 @@
+use crate::runner::{Inputs, Pool};
+use crate::utils::Parts;
+use crate::{bench, check, client, history, output, runner, session, solve_parts, submit};
+use std::io;
+use std::time::{Duration, Instant};
+
+fn exit_code(ok: bool) -> i32 {
+    if ok {
+        0
+    } else {
+        1
+    }
+}
+
+//keep the timings of full runs for compare, without getting in the way of the results
+fn record_timings(inputs: &Inputs, results: &[runner::DayResult], parts: Parts) {
+    if parts == Parts::Both {
+        if let Err(e) = history::record_results(inputs, results) {
+            eprintln!("{}", e);
+        }
+    }
+}
+
+//run the command line interface, returning the process exit code
+pub fn run(mut args: Vec<String>) -> i32 {
+    //get output format, defaulting to human-readable text
+    let format = match runner::take_flag(&mut args, "--format").map(|f| f.parse()) {
+        None => output::Format::Text,
+        Some(Ok(f)) => f,
+        Some(Err(e)) => {
+            println!("{}", e);
+            return 1;
+        }
+    };
+
+    //get which parts to run, defaulting to both
+    let parts = match runner::take_flag(&mut args, "--part").map(|p| p.parse()) {
+        None => Parts::Both,
+        Some(Ok(p)) => p,
+        Some(Err(e)) => {
+            println!("{}", e);
+            return 1;
+        }
+    };
+
+    //get where inputs live, and which of each day's named inputs to use
+    let mut inputs = Inputs::default();
+    if let Some(d) = runner::take_flag(&mut args, "--input-dir") {
+        inputs.dir = d;
+    }
+    inputs.name = runner::take_flag(&mut args, "--input");
+
+    //watch mode keeps re-running a single day as its input changes
+    let watch = runner::take_switch(&mut args, "--watch");
+
+    //get how many days run at once, and how many seconds each may take
+    let mut pool = Pool::default();
+    match runner::take_flag(&mut args, "--threads").map(|t| t.parse::<usize>()) {
+        None => {}
+        Some(Ok(t)) if t > 0 => pool.threads = t,
+        Some(_) => {
+            println!("--threads must be a positive number");
+            return 1;
+        }
+    }
+    match runner::take_flag(&mut args, "--timeout").map(|t| t.parse::<f64>()) {
+        None => {}
+        Some(Ok(t)) if t > 0.0 && t.is_finite() => pool.timeout = Some(Duration::from_secs_f64(t)),
+        Some(_) => {
+            println!("--timeout must be a positive number of seconds");
+            return 1;
+        }
+    }
+
+    //check mode compares results against the answer store in the input directory
+    if args.get(1).map(|a| a.as_str()) == Some("check") {
+        let days = args.get(2).map(|a| a.as_str()).unwrap_or("all");
+        if let Some(d) = args.get(3) {
+            inputs.dir = d.to_string();
+        }
+
+        let (report, ok) = match (
+            runner::parse_days(days),
+            check::load_expected(&check::answers_path(&inputs)),
+        ) {
+            (Err(e), _) | (_, Err(e)) => (e, false),
+            (Ok(ds), Ok(expected)) => check::check(
+                &runner::run_days(&ds, &inputs, Parts::Both, &pool),
+                &expected,
+            ),
+        };
+
+        println!("{}", report);
+        return exit_code(ok);
+    }
+
+    //compare mode runs days and compares their timings with earlier runs, flagging regressions
+    if args.get(1).map(|a| a.as_str()) == Some("compare") {
+        let baseline = runner::take_flag(&mut args, "--baseline");
+        let threshold = runner::take_flag(&mut args, "--threshold").map(|t| t.parse::<f64>());
+        let days = args.get(2).map(|a| a.as_str()).unwrap_or("all");
+        if let Some(d) = args.get(3) {
+            inputs.dir = d.to_string();
+        }
+
+        let (threshold, days, earlier) = match (
+            threshold.unwrap_or(Ok(10.0)),
+            runner::parse_days(days),
+            history::load_history(&history::history_path(&inputs)),
+        ) {
+            (Ok(t), Ok(ds), Ok(h)) if t >= 0.0 => (t / 100.0, ds, h),
+            (_, Err(e), _) | (_, _, Err(e)) => {
+                println!("{}", e);
+                return 1;
+            }
+            _ => {
+                println!("--threshold must be a non-negative percentage");
+                return 1;
+            }
+        };
+
+        let results = runner::run_days(&days, &inputs, Parts::Both, &pool);
+        for e in results.iter().filter_map(|(_, r)| r.as_ref().err()) {
+            println!("{}", e);
+        }
+
+        let (report, ok) = match history::record_results(&inputs, &results)
+            .and_then(|run| history::compare(&earlier, &run, baseline.as_deref()))
+        {
+            Ok(deltas) => history::report(&deltas, threshold),
+            Err(e) => (e, false),
+        };
+
+        println!("{}", report);
+        return exit_code(ok && results.iter().all(|(_, r)| r.is_ok()));
+    }
+
+    //bench mode repeatedly runs days against their own inputs, reporting timing statistics
+    if args.get(1).map(|a| a.as_str()) == Some("bench") {
+        let runs = runner::take_flag(&mut args, "--runs").map(|r| r.parse::<usize>());
+        let warmup = runner::take_flag(&mut args, "--warmup").map(|w| w.parse::<usize>());
+        let days = args.get(2).map(|a| a.as_str()).unwrap_or("all");
+        if let Some(d) = args.get(3) {
+            inputs.dir = d.to_string();
+        }
+
+        let (runs, warmup, days) = match (
+            runs.unwrap_or(Ok(10)),
+            warmup.unwrap_or(Ok(2)),
+            runner::parse_days(days),
+        ) {
+            (Ok(r), Ok(w), Ok(ds)) if r > 0 => (r, w, ds),
+            (_, _, Err(e)) => {
+                println!("{}", e);
+                return 1;
+            }
+            _ => {
+                println!("--runs must be a positive number and --warmup a number");
+                return 1;
+            }
+        };
+
+        let benches = bench::bench_days(&days, &inputs, runs, warmup);
+        println!("{}", bench::report(&benches));
+        return exit_code(benches.iter().all(|b| b.is_ok()));
+    }
+
+    //fetch mode downloads inputs that aren't already in the input directory
+    if args.get(1).map(|a| a.as_str()) == Some("fetch") {
+        let url = runner::take_flag(&mut args, "--url");
+        let days = args.get(2).map(|a| a.as_str()).unwrap_or("all");
+        inputs.name = None;
+        if let Some(d) = args.get(3) {
+            inputs.dir = d.to_string();
+        }
+
+        let (days, c) = match (runner::parse_days(days), client::Client::from_env(url)) {
+            (Ok(ds), Ok(c)) => (ds, c),
+            (Err(e), _) | (_, Err(e)) => {
+                println!("{}", e);
+                return 1;
+            }
+        };
+
+        let mut ok = true;
+        for d in days {
+            match client::fetch(&c, &inputs, d) {
+                Ok(true) => println!("Day {}: downloaded to {}", d, inputs.path(d)),
+                Ok(false) => println!("Day {}: already cached at {}", d, inputs.path(d)),
+                Err(e) => {
+                    ok = false;
+                    println!("Day {}: {}", d, e)
+                }
+            }
+        }
+        return exit_code(ok);
+    }
+
+    //submit mode posts one part's answer, solving the day for it if none is given
+    if args.get(1).map(|a| a.as_str()) == Some("submit") {
+        let url = runner::take_flag(&mut args, "--url");
+        let day = runner::parse_days(args.get(2).map(|a| a.as_str()).unwrap_or("")).and_then(
+            |ds| match ds[..] {
+                [d] => Ok(d),
+                _ => Err(String::from("Can only submit one day at a time")),
+            },
+        );
+        let part = match args.get(3).map(|p| p.as_str()) {
+            Some(p @ ("1" | "2")) => Ok(p.parse::<usize>().unwrap()),
+            _ => Err(String::from("Usage: submit <day> <1|2> [answer]")),
+        };
+
+        let (day, part, c) = match (day, part, client::Client::from_env(url)) {
+            (Ok(d), Ok(p), Ok(c)) => (d, p, c),
+            (Err(e), _, _) | (_, Err(e), _) | (_, _, Err(e)) => {
+                println!("{}", e);
+                return 1;
+            }
+        };
+
+        let answer = match args.get(4) {
+            Some(a) => a.to_string(),
+            None => {
+                let res = inputs.read(day).and_then(|i| {
+                    let only = if part == 1 { Parts::One } else { Parts::Two };
+                    solve_parts(day, i, only).unwrap_or_else(|| Err(runner::no_solution(day)))
+                });
+                match res.map(|a| if part == 1 { a.part1() } else { a.part2() }.map(String::from)) {
+                    Ok(Some(a)) => a,
+                    Ok(None) => {
+                        println!("Day {} part {} has no answer to submit", day, part);
+                        return 1;
+                    }
+                    Err(e) => {
+                        println!("{}", e);
+                        return 1;
+                    }
+                }
+            }
+        };
+
+        return match submit::submit(&c, &inputs, day, part, &answer) {
+            Ok(o) => {
+                println!("Day {} part {}: {} is {}", day, part, answer.trim(), o);
+                exit_code(o == submit::Outcome::Right)
+            }
+            Err(e) => {
+                println!("Day {} part {}: {}", day, part, e);
+                1
+            }
+        };
+    }
+
+    //with no day given, pick days and inputs interactively until told to quit
+    let Some(day_arg) = args.get(1) else {
+        let mut s = session::Session::new(inputs, parts, format);
+        session::run(&mut s, io::stdin().lock());
+        return 0;
+    };
+
+    let days = match runner::parse_days(day_arg) {
+        Ok(ds) => ds,
+        Err(e) => {
+            println!("{}", e);
+            return 1;
+        }
+    };
+
+    if watch && days.len() > 1 {
+        println!("--watch can only follow one day at a time");
+        return 1;
+    }
+
+    //several days: run each against its own input in the input directory
+    if days.len() > 1 {
+        if let Some(d) = args.get(2) {
+            inputs.dir = d.to_string();
+        }
+        let start = Instant::now();
+        let results = runner::run_days(&days, &inputs, parts, &pool);
+        record_timings(&inputs, &results, parts);
+        println!("{}", output::render(&results, format));
+        if format == output::Format::Text {
+            println!("Wall-clock time: {:.2?}", start.elapsed());
+        }
+        return exit_code(results.iter().all(|(_, r)| r.is_ok()));
+    }
+
+    let day = days[0];
+
+    //re-run the day against its input file every time it's saved
+    if watch {
+        let path = args.get(2).cloned().unwrap_or_else(|| inputs.path(day));
+        if path == "-" {
+            println!("--watch needs an input file, not stdin");
+            return 1;
+        }
+
+        runner::watch(&path, Duration::from_millis(250), || {
+            //clear the screen and move the cursor to the top
+            print!("\x1b[2J\x1b[H");
+            println!("Watching {} (Ctrl-C to stop)\n", path);
+            let res = runner::read_file(&path)
+                .map_err(|e| e.on_day(day))
+                .and_then(|i| runner::run_input(day, i, parts));
+            println!("{}", output::render(&[(day, res)], format));
+        });
+    }
+
+    //get input from the given file, stdin for -, or the day's own input (whose timings are kept)
+    let own_input = args.get(2).is_none();
+    let input = match args.get(2) {
+        Some(a) => runner::read_file(a).map_err(|e| e.on_day(day)),
+        None => inputs.read(day),
+    };
+
+    //get solution if one exists
+    let res = match input.map(|i| solve_parts(day, i, parts)) {
+        Ok(None) => {
+            println!("No solution for that day yet.");
+            return 0;
+        }
+        Ok(Some(res)) => res,
+        Err(e) => Err(e),
+    };
+
+    let results = [(day, res)];
+    if own_input {
+        record_timings(&inputs, &results, parts);
+    }
+
+    let ok = results[0].1.is_ok();
+    println!("{}", output::render(&results, format));
+    exit_code(ok)
+}
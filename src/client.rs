@@ -0,0 +1,202 @@
+use crate::runner::Inputs;
+use std::env;
+use std::fs;
+use std::path::Path;
+#[cfg(test)]
+use std::{
+    io::{BufRead, BufReader, Read, Write},
+    net::TcpListener,
+    sync::mpsc,
+    thread,
+};
+
+//Constants
+
+pub const DEFAULT_URL: &str = "https://adventofcode.com/2021";
+const USER_AGENT: &str = "github.com/RyanRMurray/AOC2021";
+
+//Structs
+
+//talks to the puzzle site, or anything that serves the same paths under base_url
+pub struct Client {
+    pub base_url: String,
+    session: String,
+}
+
+impl Client {
+    pub fn new(base_url: &str, session: &str) -> Self {
+        Self {
+            base_url: base_url.trim_end_matches('/').to_string(),
+            session: session.trim().to_string(),
+        }
+    }
+
+    //base url from the given override, then AOC_URL, then the real site
+    pub fn from_env(url: Option<String>) -> Result<Self, String> {
+        let base = url
+            .or_else(|| env::var("AOC_URL").ok())
+            .unwrap_or_else(|| DEFAULT_URL.to_string());
+
+        Ok(Client::new(&base, &session()?))
+    }
+
+    fn request(&self, method: &str, path: &str) -> (String, ureq::Request) {
+        let url = format!("{}{}", self.base_url, path);
+        let req = ureq::request(method, &url)
+            .set("Cookie", &format!("session={}", self.session))
+            .set("User-Agent", USER_AGENT);
+        (url, req)
+    }
+
+    fn get(&self, path: &str) -> Result<String, String> {
+        let (url, req) = self.request("GET", path);
+        body(&url, req.call())
+    }
+
+    fn post(&self, path: &str, form: &[(&str, &str)]) -> Result<String, String> {
+        let (url, req) = self.request("POST", path);
+        body(&url, req.send_form(form))
+    }
+
+    pub fn input(&self, day: usize) -> Result<String, String> {
+        self.get(&format!("/day/{}/input", day))
+    }
+
+    //post an answer, returning the page the site responds with
+    pub fn answer(&self, day: usize, part: usize, answer: &str) -> Result<String, String> {
+        self.post(
+            &format!("/day/{}/answer", day),
+            &[("level", &part.to_string()), ("answer", answer)],
+        )
+    }
+}
+
+//Functions
+
+fn body(url: &str, res: Result<ureq::Response, ureq::Error>) -> Result<String, String> {
+    match res {
+        Ok(r) => r
+            .into_string()
+            .map_err(|e| format!("Could not read response from {}: {}", url, e)),
+        //the site explains refusals in the first line of the body
+        Err(ureq::Error::Status(code, r)) => Err(format!(
+            "{} returned {}: {}",
+            url,
+            code,
+            r.into_string()
+                .unwrap_or_default()
+                .trim()
+                .lines()
+                .next()
+                .unwrap_or("")
+        )),
+        Err(e) => Err(format!("Could not reach {}: {}", url, e)),
+    }
+}
+
+//session token from AOC_SESSION, or a .aoc-session file in the current or home directory
+pub fn session() -> Result<String, String> {
+    if let Ok(s) = env::var("AOC_SESSION") {
+        return Ok(s);
+    }
+
+    let home = env::var("HOME").map(|h| format!("{}/.aoc-session", h));
+    [Ok(String::from(".aoc-session")), home]
+        .into_iter()
+        .flatten()
+        .find_map(|p| fs::read_to_string(p).ok())
+        .map(|s| s.trim().to_string())
+        .filter(|s| !s.is_empty())
+        .ok_or_else(|| {
+            String::from("No session token: set AOC_SESSION or put it in a .aoc-session file")
+        })
+}
+
+//download a day's input into the input directory, unless it's already there.
+//returns whether a download happened
+pub fn fetch(client: &Client, inputs: &Inputs, day: usize) -> Result<bool, String> {
+    let path = inputs.path(day);
+    if Path::new(&path).exists() {
+        return Ok(false);
+    }
+
+    //written beside it first, so an interrupted download never looks like a cached input
+    let input = client.input(day)?;
+    let partial = format!("{}.part", path);
+    fs::create_dir_all(&inputs.dir)
+        .and_then(|_| fs::write(&partial, input))
+        .and_then(|_| fs::rename(&partial, &path))
+        .map_err(|e| format!("Could not write {}: {}", path, e))?;
+
+    Ok(true)
+}
+
+//a stand-in for the site on a local port, answering the given number of requests with body.
+//each request's first line and form body are sent back as they arrive
+#[cfg(test)]
+pub fn stub_server(body: &'static str, requests: usize) -> (String, mpsc::Receiver<String>) {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let url = format!("http://{}", listener.local_addr().unwrap());
+    let (tx, rx) = mpsc::channel();
+
+    thread::spawn(move || {
+        for stream in listener.incoming().take(requests) {
+            let mut stream = stream.unwrap();
+            let mut reader = BufReader::new(stream.try_clone().unwrap());
+
+            let mut first = String::new();
+            reader.read_line(&mut first).unwrap();
+            let mut len = 0;
+            loop {
+                let mut header = String::new();
+                reader.read_line(&mut header).unwrap();
+                if header.trim().is_empty() {
+                    break;
+                }
+                if let Some(l) = header.to_lowercase().strip_prefix("content-length:") {
+                    len = l.trim().parse().unwrap();
+                }
+            }
+            let mut form = vec![0; len];
+            reader.read_exact(&mut form).unwrap();
+
+            write!(
+                stream,
+                "HTTP/1.1 200 OK\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                body.len(),
+                body
+            )
+            .unwrap();
+            let _ = tx.send(format!(
+                "{} {}",
+                first.trim(),
+                String::from_utf8_lossy(&form)
+            ));
+        }
+    });
+
+    (url, rx)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::runner::temp_inputs;
+
+    #[test]
+    fn fetch_caches() {
+        let (url, requests) = stub_server("1\n2\n3\n", 1);
+        let c = Client::new(&url, "token");
+        let inputs = temp_inputs("fetch");
+
+        assert_eq!(fetch(&c, &inputs, 7), Ok(true));
+        assert_eq!(requests.recv().unwrap().trim(), "GET /day/7/input HTTP/1.1");
+        assert_eq!(fs::read_to_string(inputs.path(7)).unwrap(), "1\n2\n3\n");
+        assert!(!Path::new(&format!("{}.part", inputs.path(7))).exists());
+
+        //the second fetch finds the input without asking the server
+        assert_eq!(fetch(&c, &inputs, 7), Ok(false));
+        assert!(requests.try_recv().is_err());
+        let _ = fs::remove_dir_all(&inputs.dir);
+    }
+}
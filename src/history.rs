@@ -0,0 +1,369 @@
+use crate::output::{fmt_time, table};
+use crate::runner::{DayResult, Inputs};
+use crate::utils::now;
+use std::fmt::{Display, Formatter};
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::process::Command;
+use std::time::Duration;
+
+//Structs
+
+//one day's timings from a run, keyed by the commit it ran on (or when it ran, outside a git checkout)
+#[derive(Debug, Clone, PartialEq)]
+pub struct Entry {
+    pub time: u64,
+    pub key: String,
+    pub day: usize,
+    pub parse: Option<Duration>,
+    pub part1: Option<Duration>,
+    pub part2: Option<Duration>,
+    pub overall: Duration,
+}
+
+impl Display for Entry {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let ns = |t: Option<Duration>| match t {
+            Some(t) => t.as_nanos().to_string(),
+            None => String::from("-"),
+        };
+        write!(
+            f,
+            "{} {} {} {} {} {} {}",
+            self.time,
+            self.key,
+            self.day,
+            ns(self.parse),
+            ns(self.part1),
+            ns(self.part2),
+            self.overall.as_nanos()
+        )
+    }
+}
+
+//a day's timing now against the one it's compared with
+#[derive(Debug, Clone, PartialEq)]
+pub struct Delta {
+    pub day: usize,
+    pub before: Option<Entry>,
+    pub after: Entry,
+}
+
+impl Delta {
+    //relative change in overall runtime, e.g. 0.1 for 10% slower
+    pub fn change(&self) -> Option<f64> {
+        self.before.as_ref().map(|b| {
+            self.after.overall.as_secs_f64() / b.overall.as_secs_f64().max(f64::MIN_POSITIVE) - 1.0
+        })
+    }
+
+    pub fn regressed(&self, threshold: f64) -> bool {
+        self.change().is_some_and(|c| c > threshold)
+    }
+}
+
+//Functions
+
+//timings live beside the inputs they were taken on: timings.txt, or timings.<name>.txt for named inputs
+pub fn history_path(inputs: &Inputs) -> String {
+    let dir = inputs.dir.trim_end_matches('/');
+    match &inputs.name {
+        None => format!("{}/timings.txt", dir),
+        Some(n) => format!("{}/timings.{}.txt", dir, n),
+    }
+}
+
+//the current commit, marked dirty if there are uncommitted changes, or the time if there's no git checkout
+pub fn run_key(time: u64) -> String {
+    let git = |args: &[&str]| Command::new("git").args(args).output().ok();
+
+    match git(&["rev-parse", "--short", "HEAD"]) {
+        Some(o) if o.status.success() => {
+            let hash = String::from_utf8_lossy(&o.stdout).trim().to_string();
+            match git(&["diff", "--quiet", "HEAD"]) {
+                Some(d) if !d.status.success() => format!("{}-dirty", hash),
+                _ => hash,
+            }
+        }
+        _ => time.to_string(),
+    }
+}
+
+//parse a history: one `<time> <key> <day> <parse_ns> <part1_ns> <part2_ns> <overall_ns>` per line, with - for untimed phases
+pub fn parse_history(input: &str) -> Result<Vec<Entry>, String> {
+    let mut history = vec![];
+
+    for (n, l) in input.lines().enumerate() {
+        if l.trim().is_empty() {
+            continue;
+        }
+
+        let sp: Vec<&str> = l.split_whitespace().collect();
+        let ns = |s: &str| match s {
+            "-" => Some(None),
+            s => s.parse().ok().map(|n| Some(Duration::from_nanos(n))),
+        };
+        let entry = match sp[..] {
+            [time, key, day, parse, part1, part2, overall] => (|| {
+                Some(Entry {
+                    time: time.parse().ok()?,
+                    key: key.to_string(),
+                    day: day.parse().ok()?,
+                    parse: ns(parse)?,
+                    part1: ns(part1)?,
+                    part2: ns(part2)?,
+                    overall: ns(overall)??,
+                })
+            })(),
+            _ => None,
+        };
+
+        match entry {
+            Some(e) => history.push(e),
+            None => return Err(format!("Invalid timing on line {}: `{}`", n + 1, l)),
+        }
+    }
+
+    Ok(history)
+}
+
+//a missing history file just means nothing has been timed yet
+pub fn load_history(path: &str) -> Result<Vec<Entry>, String> {
+    match fs::read_to_string(path) {
+        Ok(s) => parse_history(&s),
+        Err(_) => Ok(vec![]),
+    }
+}
+
+//the timings of each day that ran successfully, all under one key
+pub fn entries(results: &[DayResult], time: u64, key: &str) -> Vec<Entry> {
+    results
+        .iter()
+        .filter_map(|(d, r)| r.as_ref().ok().map(|a| (d, a)))
+        .map(|(d, a)| Entry {
+            time,
+            key: key.to_string(),
+            day: *d,
+            parse: a.parse_time(),
+            part1: a.part1_time(),
+            part2: a.part2_time(),
+            overall: a.overall_time(),
+        })
+        .collect()
+}
+
+pub fn record(path: &str, entries: &[Entry]) -> Result<(), String> {
+    if entries.is_empty() {
+        return Ok(());
+    }
+
+    OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(path)
+        .and_then(|mut f| entries.iter().try_for_each(|e| writeln!(f, "{}", e)))
+        .map_err(|e| format!("Could not record timings in {}: {}", path, e))
+}
+
+//time this run's successful days under the current commit, appending them to the history
+pub fn record_results(inputs: &Inputs, results: &[DayResult]) -> Result<Vec<Entry>, String> {
+    let time = now();
+    let entries = entries(results, time, &run_key(time));
+    if entries.is_empty() {
+        return Ok(entries);
+    }
+
+    fs::create_dir_all(&inputs.dir)
+        .map_err(|e| format!("Could not create {}: {}", inputs.dir, e))?;
+    record(&history_path(inputs), &entries)?;
+    Ok(entries)
+}
+
+//compare each entry of a run against the latest earlier timing of that day,
+//or against the latest earlier run whose key (commit) or time starts with the baseline
+pub fn compare(
+    earlier: &[Entry],
+    run: &[Entry],
+    baseline: Option<&str>,
+) -> Result<Vec<Delta>, String> {
+    let base_run = match baseline {
+        None => None,
+        Some(b) => Some(
+            earlier
+                .iter()
+                .rev()
+                .find(|e| e.key.starts_with(b) || e.time.to_string().starts_with(b))
+                .map(|e| (e.time, e.key.clone()))
+                .ok_or_else(|| format!("No earlier run matches baseline {}", b))?,
+        ),
+    };
+
+    Ok(run
+        .iter()
+        .map(|after| {
+            let before = earlier
+                .iter()
+                .rev()
+                .filter(|e| e.day == after.day)
+                .find(|e| match &base_run {
+                    Some((t, k)) => e.time == *t && e.key == *k,
+                    None => true,
+                })
+                .cloned();
+
+            Delta {
+                day: after.day,
+                before,
+                after: after.clone(),
+            }
+        })
+        .collect())
+}
+
+//a table of overall runtime changes, flagging days that slowed down by more than the threshold,
+//along with whether none did
+pub fn report(deltas: &[Delta], threshold: f64) -> (String, bool) {
+    let header = ["Day", "Against", "Before", "After", "Change", ""];
+
+    let rows: Vec<Vec<String>> = deltas
+        .iter()
+        .map(|d| {
+            vec![
+                d.day.to_string(),
+                d.before
+                    .as_ref()
+                    .map_or(String::from("-"), |b| b.key.clone()),
+                fmt_time(d.before.as_ref().map(|b| b.overall)),
+                fmt_time(Some(d.after.overall)),
+                d.change()
+                    .map_or(String::from("-"), |c| format!("{:+.1}%", c * 100.0)),
+                String::from(if d.regressed(threshold) {
+                    "REGRESSION"
+                } else {
+                    ""
+                }),
+            ]
+        })
+        .collect();
+
+    //totals over the days that have something to compare against
+    let compared: Vec<&Delta> = deltas.iter().filter(|d| d.before.is_some()).collect();
+    let total = |f: &dyn Fn(&Delta) -> Duration| compared.iter().map(|d| f(d)).sum::<Duration>();
+    let (before, after) = (
+        total(&|d| d.before.as_ref().unwrap().overall),
+        total(&|d| d.after.overall),
+    );
+
+    let regressions = deltas.iter().filter(|d| d.regressed(threshold)).count();
+    let mut res = table(&header, &rows, &[1, 5]);
+    if !compared.is_empty() {
+        res.push_str(&format!(
+            "\nTotal of compared days: {} -> {} ({:+.1}%)",
+            fmt_time(Some(before)),
+            fmt_time(Some(after)),
+            (after.as_secs_f64() / before.as_secs_f64().max(f64::MIN_POSITIVE) - 1.0) * 100.0
+        ));
+    }
+    res.push_str(&format!(
+        "\n{} of {} days slowed down by more than {:.1}%",
+        regressions,
+        compared.len(),
+        threshold * 100.0
+    ));
+
+    (res, regressions == 0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(time: u64, key: &str, day: usize, overall_ms: u64) -> Entry {
+        Entry {
+            time,
+            key: key.to_string(),
+            day,
+            parse: Some(Duration::from_micros(50)),
+            part1: Some(Duration::from_millis(overall_ms / 2)),
+            part2: None,
+            overall: Duration::from_millis(overall_ms),
+        }
+    }
+
+    #[test]
+    fn history_round_trip() {
+        let history = vec![
+            entry(1000, "abc1234", 1, 10),
+            entry(1000, "abc1234-dirty", 2, 7),
+        ];
+        let text: String = history.iter().map(|e| format!("{}\n\n", e)).collect();
+        assert!(text.starts_with("1000 abc1234 1 50000 5000000 - 10000000\n"));
+        assert_eq!(parse_history(&text), Ok(history));
+
+        for bad in [
+            "1000 abc1234 1 50000 5000000 -",
+            "1000 abc1234 x 50000 5000000 - 10000000",
+            "1000 abc1234 1 50000 5000000 - -",
+        ] {
+            assert_eq!(
+                parse_history(&format!("1000 k 1 - - - 5\n{}", bad)),
+                Err(format!("Invalid timing on line 2: `{}`", bad))
+            );
+        }
+    }
+
+    #[test]
+    fn comparing() {
+        let earlier = vec![
+            entry(1000, "aaa1111", 1, 10),
+            entry(1000, "aaa1111", 2, 20),
+            entry(2000, "bbb2222", 1, 12),
+        ];
+        let run = vec![entry(3000, "ccc3333", 1, 11), entry(3000, "ccc3333", 3, 5)];
+
+        //without a baseline, each day is against its own latest timing
+        let latest = compare(&earlier, &run, None).unwrap();
+        let against = |ds: &[Delta]| {
+            ds.iter()
+                .map(|d| d.before.as_ref().map(|b| b.key.clone()))
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(against(&latest), vec![Some(String::from("bbb2222")), None]);
+
+        //a baseline matches the start of a run's key or its time
+        let by_key = compare(&earlier, &run, Some("aaa")).unwrap();
+        assert_eq!(against(&by_key), vec![Some(String::from("aaa1111")), None]);
+        assert_eq!(by_key[0].change().map(|c| (c * 100.0).round()), Some(10.0));
+        let by_time = compare(&earlier, &run, Some("200")).unwrap();
+        assert_eq!(against(&by_time), vec![Some(String::from("bbb2222")), None]);
+
+        assert_eq!(
+            compare(&earlier, &run, Some("zzz")).err(),
+            Some(String::from("No earlier run matches baseline zzz"))
+        );
+    }
+
+    #[test]
+    fn reporting() {
+        let earlier = vec![entry(1000, "aaa1111", 1, 10), entry(1000, "aaa1111", 2, 20)];
+        let run = vec![
+            entry(2000, "bbb2222", 1, 12),
+            entry(2000, "bbb2222", 2, 20),
+            entry(2000, "bbb2222", 3, 5),
+        ];
+        let deltas = compare(&earlier, &run, None).unwrap();
+
+        //day 1 slowed down by 20%, which is only a regression under a lower threshold
+        let (strict, ok) = report(&deltas, 0.1);
+        assert!(!ok);
+        assert_eq!(strict.matches("REGRESSION").count(), 1);
+        assert!(strict.contains("+20.0%") && strict.contains("+0.0%"));
+        assert!(strict.contains("Total of compared days: 30.00ms -> 32.00ms (+6.7%)"));
+        assert!(strict.ends_with("1 of 2 days slowed down by more than 10.0%"));
+
+        let (lenient, ok) = report(&deltas, 0.25);
+        assert!(ok);
+        assert!(!lenient.contains("REGRESSION"));
+        assert!(lenient.ends_with("0 of 2 days slowed down by more than 25.0%"));
+    }
+}
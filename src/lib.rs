@@ -0,0 +1,30 @@
+pub mod alloc;
+pub mod bench;
+pub mod check;
+pub mod cli;
+pub mod client;
+pub mod history;
+pub mod output;
+pub mod runner;
+pub mod session;
+pub mod solutions;
+pub mod submit;
+pub mod utils;
+extern crate eval;
+
+pub use utils::{
+    bit_to_n, checkpoint, simple_parse, Answer, Axis, Day, DenseGrid, Grid, Parts, Phase, Point,
+    Pt2d, Pt3d, PtN, SolveError, Timing,
+};
+
+//run a day's solution against the given input, if a solution exists
+pub fn solve(day: usize, input: String) -> Option<Result<Answer, SolveError>> {
+    solve_parts(day, input, Parts::Both)
+}
+
+//like solve, but only running the selected parts
+pub fn solve_parts(day: usize, input: String, parts: Parts) -> Option<Result<Answer, SolveError>> {
+    solutions::DAYS
+        .get(day.wrapping_sub(1))
+        .map(|sol| sol(input, parts).map_err(|e| e.on_day(day)))
+}
@@ -0,0 +1,423 @@
+use crate::alloc::Memory;
+use crate::runner::DayResult;
+use crate::utils::{fmt_bytes, Answer, Phase, SolveError};
+use std::str::FromStr;
+use std::time::Duration;
+
+//Types
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Format {
+    Text,
+    Json,
+    Csv,
+}
+
+impl FromStr for Format {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_lowercase().as_str() {
+            "text" => Ok(Format::Text),
+            "json" => Ok(Format::Json),
+            "csv" => Ok(Format::Csv),
+            f => Err(format!(
+                "Unknown output format {} (expected json, csv or text)",
+                f
+            )),
+        }
+    }
+}
+
+//Functions
+
+pub fn render(results: &[DayResult], format: Format) -> String {
+    match format {
+        Format::Text => text(results),
+        Format::Json => json(results),
+        Format::Csv => csv(results),
+    }
+}
+
+fn text(results: &[DayResult]) -> String {
+    match results {
+        [(day, Ok(a))] => format!("===========Day {}===========\n{}", day, a),
+        [(_, Err(e))] => e.to_string(),
+        _ => summary_table(results),
+    }
+}
+
+fn nanos(t: Option<Duration>) -> Option<u128> {
+    t.map(|t| t.as_nanos())
+}
+
+//the structured fields shared by json and csv, in column order.
+//timings are in nanoseconds; a part's result and runtime are empty if it wasn't run.
+//memory is in bytes, and empty unless allocations are being counted
+fn fields(a: &Answer) -> [(&'static str, Option<String>); 14] {
+    let mem = |m: Option<Memory>, f: fn(Memory) -> usize| m.map(|m| f(m).to_string());
+    let (parse, p1, p2) = (
+        a.memory(Phase::Parse),
+        a.memory(Phase::Part1),
+        a.memory(Phase::Part2),
+    );
+
+    [
+        ("part1", a.part1().map(String::from)),
+        ("part2", a.part2().map(String::from)),
+        ("parse_ns", nanos(a.parse_time()).map(|t| t.to_string())),
+        ("part1_ns", nanos(a.part1_time()).map(|t| t.to_string())),
+        ("part2_ns", nanos(a.part2_time()).map(|t| t.to_string())),
+        ("overall_ns", Some(a.overall_time().as_nanos().to_string())),
+        ("parse_peak_bytes", mem(parse, |m| m.peak)),
+        ("parse_total_bytes", mem(parse, |m| m.total)),
+        ("part1_peak_bytes", mem(p1, |m| m.peak)),
+        ("part1_total_bytes", mem(p1, |m| m.total)),
+        ("part2_peak_bytes", mem(p2, |m| m.peak)),
+        ("part2_total_bytes", mem(p2, |m| m.total)),
+        ("overall_peak_bytes", mem(a.overall_memory(), |m| m.peak)),
+        ("overall_total_bytes", mem(a.overall_memory(), |m| m.total)),
+    ]
+}
+
+fn json_str(s: &str) -> String {
+    let mut res = String::from("\"");
+    for c in s.chars() {
+        match c {
+            '"' => res.push_str("\\\""),
+            '\\' => res.push_str("\\\\"),
+            '\n' => res.push_str("\\n"),
+            '\r' => res.push_str("\\r"),
+            '\t' => res.push_str("\\t"),
+            c if (c as u32) < 0x20 => res.push_str(&format!("\\u{:04x}", c as u32)),
+            c => res.push(c),
+        }
+    }
+    res.push('"');
+    res
+}
+
+fn json_num(n: Option<usize>) -> String {
+    n.map(|n| n.to_string())
+        .unwrap_or_else(|| String::from("null"))
+}
+
+fn json_error(e: &SolveError) -> String {
+    format!(
+        "{{\"phase\":{},\"line\":{},\"col\":{},\"msg\":{}}}",
+        json_str(&e.phase.to_string()),
+        json_num(e.line),
+        json_num(e.col),
+        json_str(&e.msg)
+    )
+}
+
+//results beyond the two parts, and each phase's checkpoints as `phase: name`
+fn extras(a: &Answer) -> Vec<(String, String)> {
+    a.extras().cloned().collect()
+}
+
+fn checkpoints(a: &Answer) -> Vec<(String, u128)> {
+    a.timings()
+        .iter()
+        .flat_map(|t| {
+            t.checkpoints
+                .iter()
+                .map(move |(n, d)| (format!("{}: {}", t.phase, n), d.as_nanos()))
+        })
+        .collect()
+}
+
+fn json(results: &[DayResult]) -> String {
+    let objs: Vec<String> = results
+        .iter()
+        .map(|(day, res)| {
+            let mut kvs = vec![format!("\"day\":{}", day)];
+            match res {
+                Ok(a) => {
+                    kvs.extend(fields(a).iter().map(|(k, v)| {
+                        let v = match (v, k.ends_with("_ns") || k.ends_with("_bytes")) {
+                            (None, _) => String::from("null"),
+                            (Some(v), true) => v.clone(),
+                            (Some(v), false) => json_str(v),
+                        };
+                        format!("\"{}\":{}", k, v)
+                    }));
+
+                    let extras: Vec<String> = extras(a)
+                        .iter()
+                        .map(|(n, r)| {
+                            format!("{{\"name\":{},\"result\":{}}}", json_str(n), json_str(r))
+                        })
+                        .collect();
+                    let cps: Vec<String> = checkpoints(a)
+                        .iter()
+                        .map(|(n, t)| format!("{{\"name\":{},\"ns\":{}}}", json_str(n), t))
+                        .collect();
+                    kvs.push(format!("\"extras\":[{}]", extras.join(",")));
+                    kvs.push(format!("\"checkpoints\":[{}]", cps.join(",")));
+                }
+                Err(e) => kvs.push(format!("\"error\":{}", json_error(e))),
+            }
+            format!("{{{}}}", kvs.join(","))
+        })
+        .collect();
+
+    format!("[{}]", objs.join(",\n "))
+}
+
+fn csv_field(s: &str) -> String {
+    if s.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", s.replace('"', "\"\""))
+    } else {
+        s.to_string()
+    }
+}
+
+fn csv(results: &[DayResult]) -> String {
+    let columns: Vec<&str> = fields(&Answer::default())
+        .iter()
+        .map(|(k, _)| *k)
+        .chain(["extras", "checkpoints_ns"])
+        .collect();
+    let mut res = format!("day,{},error", columns.join(","));
+
+    for (day, r) in results {
+        let cols: Vec<String> = match r {
+            //extras and checkpoints are `name=value` pairs separated by semicolons
+            Ok(a) => fields(a)
+                .iter()
+                .map(|(_, v)| csv_field(v.as_deref().unwrap_or("")))
+                .chain([
+                    csv_field(&pairs(extras(a))),
+                    csv_field(&pairs(checkpoints(a))),
+                    String::new(),
+                ])
+                .collect(),
+            Err(e) => vec![String::new(); columns.len()]
+                .into_iter()
+                .chain([csv_field(&e.to_string())])
+                .collect(),
+        };
+        res.push_str(&format!("\n{},{}", day, cols.join(",")));
+    }
+    res
+}
+
+fn pairs<T: std::fmt::Display>(ps: Vec<(String, T)>) -> String {
+    ps.iter()
+        .map(|(n, v)| format!("{}={}", n, v))
+        .collect::<Vec<_>>()
+        .join(";")
+}
+
+//Summary table
+
+pub fn fmt_time(t: Option<Duration>) -> String {
+    match t {
+        Some(t) => format!("{:.2?}", t),
+        None => String::from("-"),
+    }
+}
+
+//multi-line results (e.g. printed grids) don't fit in a cell, so they're shown under the table,
+//along with any results beyond the two parts
+fn is_multiline(r: &str) -> bool {
+    r.trim().contains('\n')
+}
+
+fn fmt_result(r: Option<&str>) -> String {
+    match r {
+        Some(r) if is_multiline(r) => String::from("(see below)"),
+        Some(r) => r.trim().to_string(),
+        None => String::from("-"),
+    }
+}
+
+//lay out rows under a header, left-aligning the given columns and right-aligning the rest
+pub fn table(header: &[&str], rows: &[Vec<String>], left: &[usize]) -> String {
+    let header: Vec<String> = header.iter().map(|h| h.to_string()).collect();
+
+    let widths: Vec<usize> = (0..header.len())
+        .map(|i| {
+            rows.iter()
+                .chain([&header])
+                .map(|r| r.get(i).map(|c| c.chars().count()).unwrap_or(0))
+                .max()
+                .unwrap()
+        })
+        .collect();
+
+    let fmt_row = |r: &Vec<String>| {
+        widths
+            .iter()
+            .enumerate()
+            .map(|(i, w)| {
+                let c = r.get(i).map(|c| c.as_str()).unwrap_or("");
+                if left.contains(&i) {
+                    format!("{:<w$}", c, w = w)
+                } else {
+                    format!("{:>w$}", c, w = w)
+                }
+            })
+            .collect::<Vec<_>>()
+            .join(" | ")
+    };
+
+    let mut res = fmt_row(&header);
+    res.push('\n');
+    res.push_str(
+        &widths
+            .iter()
+            .map(|w| "-".repeat(*w))
+            .collect::<Vec<_>>()
+            .join("-+-"),
+    );
+    for r in rows {
+        res.push('\n');
+        res.push_str(&fmt_row(r));
+    }
+
+    res
+}
+
+pub fn summary_table(results: &[DayResult]) -> String {
+    let mut header = vec![
+        "Day", "Part 1", "Part 2", "Parse", "Part 1 t", "Part 2 t", "Overall",
+    ];
+
+    //memory columns only appear when allocations are being counted
+    let mem = results
+        .iter()
+        .any(|(_, r)| matches!(r, Ok(a) if a.overall_memory().is_some()));
+    if mem {
+        header.extend(["Peak mem", "Allocated"]);
+    }
+
+    let mut rows: Vec<Vec<String>> = vec![];
+    let mut extras = String::new();
+    let mut total = Duration::ZERO;
+
+    for (day, res) in results {
+        rows.push(match res {
+            Ok(a) => {
+                total += a.overall_time();
+                for (name, r) in [("Part 1", a.part1()), ("Part 2", a.part2())] {
+                    if let Some(r) = r.filter(|r| is_multiline(r)) {
+                        extras.push_str(&format!("\nDay {} {}:{}", day, name, r));
+                    }
+                }
+                for (name, r) in a.extras() {
+                    extras.push_str(&format!("\nDay {} {}: {}", day, name, r));
+                }
+                let mut row = vec![
+                    day.to_string(),
+                    fmt_result(a.part1()),
+                    fmt_result(a.part2()),
+                    fmt_time(a.parse_time()),
+                    fmt_time(a.part1_time()),
+                    fmt_time(a.part2_time()),
+                    fmt_time(Some(a.overall_time())),
+                ];
+                if let Some(m) = a.overall_memory().filter(|_| mem) {
+                    row.extend([fmt_bytes(m.peak), fmt_bytes(m.total)]);
+                }
+                row
+            }
+            Err(e) => vec![day.to_string(), e.to_string()],
+        });
+    }
+
+    //results are left-aligned, numbers and times right-aligned
+    let mut res = table(&header, &rows, &[1, 2]);
+    res.push_str(&format!("\nTotal runtime: {:.2?}", total));
+    res.push_str(&extras);
+
+    res
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::runner::run_input;
+    use crate::solutions::day13::Day13;
+    use crate::utils::{Day, Parts};
+
+    const AWKWARD: &str = "x,\"y\"\nz";
+
+    //a result full of separators and quotes, the day 13 render in its extras, and day 13's error
+    fn results() -> Vec<DayResult> {
+        let mut a = Answer::default();
+        a.record("Part 1", &AWKWARD);
+
+        let ex =
+            "6,10\n0,14\n9,10\n0,3\n10,4\n4,11\n6,0\n6,12\n4,1\n0,13\n10,12\n3,4\n3,0\n8,4\n1,10\n\
+                  2,14\n8,10\n9,0\n\nfold along y=7\nfold along x=5";
+        let parsed = Day13::parse(ex.to_string()).unwrap();
+        let (_, render) = Day13::extras(&parsed).unwrap().remove(0);
+        a.record("Part 2 render", &render);
+
+        vec![(1, Ok(a)), (13, run_input(13, ex.to_string(), Parts::Both))]
+    }
+
+    //split csv into records of fields, honouring quotes
+    fn records(s: &str) -> Vec<Vec<String>> {
+        let (mut res, mut row, mut field) = (vec![], vec![], String::new());
+        let mut cs = s.chars().peekable();
+        let mut quoted = false;
+        while let Some(c) = cs.next() {
+            match (c, quoted) {
+                ('"', true) if cs.peek() == Some(&'"') => {
+                    cs.next();
+                    field.push('"');
+                }
+                ('"', _) => quoted = !quoted,
+                (',', false) => row.push(std::mem::take(&mut field)),
+                ('\n', false) => {
+                    row.push(std::mem::take(&mut field));
+                    res.push(std::mem::take(&mut row));
+                }
+                (c, _) => field.push(c),
+            }
+        }
+        row.push(field);
+        res.push(row);
+        res
+    }
+
+    #[test]
+    fn csv_quoting() {
+        let rs = records(&render(&results(), Format::Csv));
+        assert_eq!(rs.len(), 3);
+        assert!(rs.iter().all(|r| r.len() == rs[0].len()));
+        assert_eq!(
+            (rs[0][1].as_str(), rs[0].last().unwrap().as_str()),
+            ("part1", "error")
+        );
+
+        let extras_col = rs[0].iter().position(|c| c == "extras").unwrap();
+        assert_eq!(rs[1][1], AWKWARD);
+        assert!(rs[1][extras_col].starts_with("Part 2 render=\n█████\n█   █\n"));
+
+        let err = rs[2].last().unwrap();
+        assert!(err.starts_with("Day 13 ") && err.contains("in the message:\n█████\n"));
+        assert!(rs[2][1..rs[2].len() - 1].iter().all(|c| c.is_empty()));
+    }
+
+    #[test]
+    fn json_escaping() {
+        assert_eq!(
+            json_str("a\"b\\c\nd\te\u{1}"),
+            "\"a\\\"b\\\\c\\nd\\te\\u0001\""
+        );
+
+        //one object per line, with every newline in a value escaped
+        let out = render(&results(), Format::Json);
+        assert_eq!(out.lines().count(), 2);
+        assert!(out.contains("\"part1\":\"x,\\\"y\\\"\\nz\""));
+        assert!(out.contains("\"result\":\"\\n█████\\n█   █\\n"));
+        assert!(out.contains(
+            "\"error\":{\"phase\":\"part 2\",\"line\":null,\"col\":null,\"msg\":\"Unrecognised"
+        ));
+    }
+}
@@ -0,0 +1,385 @@
+use crate::alloc::Measure;
+use crate::solutions::DAYS;
+use crate::solve_parts;
+use crate::utils::parse::normalise;
+use crate::utils::{take_checkpoints, Answer, Day, Parts, Phase, SolveError, Timing};
+use std::any::Any;
+use std::cell::Cell;
+use std::collections::{HashMap, VecDeque};
+use std::fs;
+use std::io::{self, Read};
+use std::panic::{self, AssertUnwindSafe};
+use std::sync::mpsc;
+use std::thread;
+use std::time::{Duration, Instant};
+
+//Types
+
+pub type DayResult = (usize, Result<Answer, SolveError>);
+
+//Structs
+
+//where each day's input lives: dir/dayNN.txt, or dir/dayNN.<name>.txt for a named input like `example`
+#[derive(Debug, Clone, PartialEq)]
+pub struct Inputs {
+    pub dir: String,
+    pub name: Option<String>,
+}
+
+impl Default for Inputs {
+    fn default() -> Self {
+        Self {
+            dir: String::from("./inputs"),
+            name: None,
+        }
+    }
+}
+
+//how many days run at once, and how long each may run before it's given up on
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Pool {
+    pub threads: usize,
+    pub timeout: Option<Duration>,
+}
+
+impl Default for Pool {
+    fn default() -> Self {
+        Self {
+            threads: thread::available_parallelism().map_or(1, |n| n.get()),
+            timeout: None,
+        }
+    }
+}
+
+impl Inputs {
+    pub fn path(&self, day: usize) -> String {
+        let dir = self.dir.trim_end_matches('/');
+        match &self.name {
+            None => format!("{}/day{:02}.txt", dir, day),
+            Some(n) => format!("{}/day{:02}.{}.txt", dir, day, n),
+        }
+    }
+
+    pub fn read(&self, day: usize) -> Result<String, SolveError> {
+        read_file(&self.path(day)).map_err(|e| e.on_day(day))
+    }
+}
+
+//Statics
+
+//the phase this thread is running, so a panic can be blamed on it
+thread_local! {
+    static PHASE: Cell<Phase> = const { Cell::new(Phase::Input) };
+}
+
+//Functions
+
+//run one phase, timing it along with any checkpoints it marks, and measuring its allocations
+fn timed<T>(
+    phase: Phase,
+    f: impl FnOnce() -> Result<T, SolveError>,
+) -> Result<(T, Timing), SolveError> {
+    PHASE.with(|p| p.set(phase));
+    take_checkpoints(Instant::now());
+    let mem = Measure::start();
+    let start = Instant::now();
+    let res = f();
+    let time = start.elapsed();
+    let memory = mem.finish();
+
+    let checkpoints = take_checkpoints(start);
+    let res = res.map_err(|e| {
+        if phase == Phase::Parse {
+            e
+        } else {
+            e.during(phase)
+        }
+    })?;
+    Ok((
+        res,
+        Timing {
+            phase,
+            time,
+            checkpoints,
+            memory,
+        },
+    ))
+}
+
+//parse the input and run the selected parts, timing each phase separately
+pub fn run<D: Day>(input: String, parts: Parts) -> Result<Answer, SolveError> {
+    let mut answer = Answer::default();
+
+    //days see \n line endings and no trailing newline, however the input was saved
+    let (parsed, t) = timed(Phase::Parse, || D::parse(normalise(&input)))?;
+    answer.record_time(t);
+
+    if parts.has(1) {
+        let (res, t) = timed(Phase::Part1, || D::part1(&parsed).map(|r| r.to_string()))?;
+        answer.record("Part 1", &res);
+        answer.record_time(t);
+    }
+    if parts.has(2) {
+        let (res, t) = timed(Phase::Part2, || D::part2(&parsed).map(|r| r.to_string()))?;
+        answer.record("Part 2", &res);
+        answer.record_time(t);
+    }
+
+    //extras are only worth running alongside everything else
+    if parts == Parts::Both {
+        let (extras, t) = timed(Phase::Extras, || D::extras(&parsed))?;
+        if !extras.is_empty() {
+            for (name, res) in &extras {
+                answer.record(name, res);
+            }
+            answer.record_time(t);
+        }
+    }
+
+    Ok(answer)
+}
+
+//parse a day selection: a single day, an inclusive range like 3-9, or all
+pub fn parse_days(arg: &str) -> Result<Vec<usize>, String> {
+    let arg = arg.trim();
+
+    let (from, to) = match arg.split_once('-') {
+        _ if arg == "all" => (1, DAYS.len()),
+        None => {
+            let d = parse_day(arg)?;
+            (d, d)
+        }
+        Some((a, b)) => (parse_day(a)?, parse_day(b)?),
+    };
+
+    if from > to {
+        return Err(format!("Invalid day range {}", arg));
+    }
+
+    Ok((from..to + 1).collect())
+}
+
+fn parse_day(arg: &str) -> Result<usize, String> {
+    match arg.trim().parse() {
+        Ok(d) if (1..=25).contains(&d) => Ok(d),
+        Ok(_) => Err(String::from("Not a valid day >:[")),
+        Err(_) => Err(String::from("Invalid day number format.")),
+    }
+}
+
+//read an input file, or stdin if the path is -
+pub fn read_file(path: &str) -> Result<String, SolveError> {
+    let mut input = String::new();
+    let res = match path {
+        "-" => io::stdin().read_to_string(&mut input).map(|_| input),
+        _ => fs::read_to_string(path),
+    };
+
+    res.map_err(|e| SolveError::new(Phase::Input, format!("Could not open {}: {}", path, e)))
+}
+
+fn panic_msg(p: Box<dyn Any + Send>) -> String {
+    match (p.downcast_ref::<&str>(), p.downcast_ref::<String>()) {
+        (Some(s), _) => s.to_string(),
+        (_, Some(s)) => s.clone(),
+        _ => String::from("unknown panic"),
+    }
+}
+
+//the error for a day that can't be run because it hasn't been solved
+pub fn no_solution(day: usize) -> SolveError {
+    SolveError::new(Phase::Input, "No solution for that day yet.").on_day(day)
+}
+
+//run the selected parts of a day against its own input, turning a panic into an error
+pub fn run_day(day: usize, inputs: &Inputs, parts: Parts) -> Result<Answer, SolveError> {
+    if day > DAYS.len() {
+        return Err(no_solution(day));
+    }
+    run_input(day, inputs.read(day)?, parts)
+}
+
+//run the selected parts of a day against the given input, turning a panic into an error
+pub fn run_input(day: usize, input: String, parts: Parts) -> Result<Answer, SolveError> {
+    PHASE.with(|p| p.set(Phase::Parse));
+
+    match panic::catch_unwind(AssertUnwindSafe(|| solve_parts(day, input, parts))) {
+        Ok(Some(res)) => res,
+        Ok(None) => Err(no_solution(day)),
+        Err(p) => Err(SolveError::new(
+            PHASE.with(|p| p.get()),
+            format!("panicked: {}", panic_msg(p)),
+        )
+        .on_day(day)),
+    }
+}
+
+//run the selected parts of each day against its own input, several days at once.
+//results come back in day order, with days that run past the timeout abandoned and marked as timed out,
+//and days without a solution marked as such
+pub fn run_days(days: &[usize], inputs: &Inputs, parts: Parts, pool: &Pool) -> Vec<DayResult> {
+    let mut queue: VecDeque<usize> = days.iter().cloned().collect();
+    let mut running: HashMap<usize, Instant> = HashMap::new();
+    let mut results: HashMap<usize, Result<Answer, SolveError>> = HashMap::new();
+    let (tx, rx) = mpsc::channel();
+
+    while results.len() < days.len() {
+        //start days until every thread is busy
+        while running.len() < pool.threads.max(1) {
+            let Some(d) = queue.pop_front() else {
+                break;
+            };
+            let (tx, inputs) = (tx.clone(), inputs.clone());
+            running.insert(d, Instant::now());
+            thread::spawn(move || tx.send((d, run_day(d, &inputs, parts))));
+        }
+
+        //wait for the next day to finish, or the next one to run out of time
+        let next_deadline = pool
+            .timeout
+            .and_then(|t| running.values().map(|s| *s + t).min());
+        let msg = match next_deadline {
+            Some(dl) => rx
+                .recv_timeout(dl.saturating_duration_since(Instant::now()))
+                .ok(),
+            None => rx.recv().ok(),
+        };
+
+        match msg {
+            //abandoned days may still finish, but they've already been reported
+            Some((d, res)) => {
+                if running.remove(&d).is_some() {
+                    results.insert(d, res);
+                }
+            }
+            None => {
+                let t = pool.timeout.unwrap_or_default();
+                let expired: Vec<usize> = running
+                    .iter()
+                    .filter(|(_, s)| s.elapsed() >= t)
+                    .map(|(d, _)| *d)
+                    .collect();
+                for d in expired {
+                    running.remove(&d);
+                    let msg = format!("no result after {:.2?}", t);
+                    results.insert(d, Err(SolveError::new(Phase::Timeout, msg).on_day(d)));
+                }
+            }
+        }
+    }
+
+    days.iter()
+        .map(|d| (*d, results.remove(d).unwrap()))
+        .collect()
+}
+
+//call f now, and again every time the file at path changes, polling its modification time and size.
+//a missing file (e.g. mid-save) is waited out rather than treated as a change
+pub fn watch(path: &str, interval: Duration, mut f: impl FnMut()) -> ! {
+    let stamp = || {
+        fs::metadata(path)
+            .ok()
+            .map(|m| (m.modified().ok(), m.len()))
+    };
+    let mut last = stamp();
+    f();
+
+    loop {
+        thread::sleep(interval);
+        let now = stamp();
+        if now.is_some() && now != last {
+            last = now;
+            f();
+        }
+    }
+}
+
+//pull a `--name` switch out of the argument list, returning whether it was there
+pub fn take_switch(args: &mut Vec<String>, name: &str) -> bool {
+    match args.iter().position(|a| a == name) {
+        Some(i) => {
+            args.remove(i);
+            true
+        }
+        None => false,
+    }
+}
+
+//pull a `--name value` or `--name=value` flag out of the argument list
+pub fn take_flag(args: &mut Vec<String>, name: &str) -> Option<String> {
+    let prefix = format!("{}=", name);
+    let i = args
+        .iter()
+        .position(|a| a == name || a.starts_with(&prefix))?;
+
+    let a = args.remove(i);
+    match a.strip_prefix(&prefix) {
+        Some(v) => Some(v.to_string()),
+        None if i < args.len() => Some(args.remove(i)),
+        None => None,
+    }
+}
+
+//inputs in a fresh directory under the system's temp directory, which doesn't exist yet
+#[cfg(test)]
+pub fn temp_inputs(name: &str) -> Inputs {
+    let dir = std::env::temp_dir().join(format!("aoc2021-{}-{}", name, std::process::id()));
+    let _ = fs::remove_dir_all(&dir);
+    Inputs {
+        dir: dir.to_string_lossy().to_string(),
+        name: None,
+    }
+}
+
+//run a day on a worked example, checking whichever parts have an expected result
+#[cfg(test)]
+pub fn assert_example<D: Day>(input: &str, part1: Option<&str>, part2: Option<&str>) {
+    let parts = match (part1, part2) {
+        (Some(_), None) => Parts::One,
+        (None, Some(_)) => Parts::Two,
+        _ => Parts::Both,
+    };
+    let a = run::<D>(input.to_string(), parts).unwrap_or_else(|e| panic!("{}", e));
+
+    if part1.is_some() {
+        assert_eq!(a.part1(), part1, "part 1 of {:?}", input);
+    }
+    if part2.is_some() {
+        assert_eq!(a.part2(), part2, "part 2 of {:?}", input);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    //days past the last solution are reported rather than dropped, before looking for their input
+    #[test]
+    fn unsolved_days() {
+        let inputs = temp_inputs("unsolved");
+        let days: Vec<usize> = (DAYS.len()..=25).collect();
+        let pool = Pool {
+            threads: 2,
+            timeout: None,
+        };
+
+        let results = run_days(&days, &inputs, Parts::Both, &pool);
+        assert_eq!(results.iter().map(|(d, _)| *d).collect::<Vec<_>>(), days);
+        assert!(results[0]
+            .1
+            .as_ref()
+            .err()
+            .unwrap()
+            .msg
+            .starts_with("Could not open"));
+        for (d, r) in &results[1..] {
+            assert_eq!(r.as_ref().err(), Some(&no_solution(*d)));
+        }
+
+        let benches = crate::bench::bench_days(&days[1..], &inputs, 1, 0);
+        assert_eq!(benches.len(), days.len() - 1);
+        assert!(benches.iter().all(|b| b
+            .as_ref()
+            .is_err_and(|e| e.msg == "No solution for that day yet.")));
+    }
+}
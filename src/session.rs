@@ -0,0 +1,339 @@
+use crate::output::{self, fmt_time, table, Format};
+use crate::runner::{self, Inputs};
+use crate::solutions::DAYS;
+use crate::utils::{Answer, Parts, SolveError};
+use std::collections::VecDeque;
+use std::io::{self, BufRead, Write};
+
+//Types
+
+//where the selected day's input comes from
+#[derive(Debug, Clone, PartialEq)]
+pub enum Source {
+    //the day's own input in the input directory (named or not)
+    Own,
+    //a file given by path
+    File(String),
+}
+
+//Structs
+
+//one run of a day, kept for the history
+pub struct Run {
+    pub day: usize,
+    pub input: String,
+    pub parts: Parts,
+    pub res: Result<Answer, SolveError>,
+}
+
+//what's selected, and what's been run so far
+pub struct Session {
+    pub day: Option<usize>,
+    pub inputs: Inputs,
+    pub source: Source,
+    pub parts: Parts,
+    pub format: Format,
+    pub runs: VecDeque<Run>,
+}
+
+impl Session {
+    pub fn new(inputs: Inputs, parts: Parts, format: Format) -> Self {
+        Self {
+            day: None,
+            inputs,
+            source: Source::Own,
+            parts,
+            format,
+            runs: VecDeque::new(),
+        }
+    }
+
+    fn input_path(&self, day: usize) -> String {
+        match &self.source {
+            Source::Own => self.inputs.path(day),
+            Source::File(f) => f.clone(),
+        }
+    }
+
+    //the current selection, shown as the prompt
+    fn prompt(&self) -> String {
+        let parts = match self.parts {
+            Parts::Both => "both parts",
+            Parts::One => "part 1",
+            Parts::Two => "part 2",
+        };
+        match self.day {
+            Some(d) => format!("[day {}, {}, {}]> ", d, self.input_path(d), parts),
+            None => format!("[no day, {}]> ", parts),
+        }
+    }
+
+    //run the selected day and keep the result, returning what to show
+    fn run(&mut self) -> String {
+        let Some(day) = self.day else {
+            return String::from("Pick a day first, e.g. `day 5`");
+        };
+
+        let input = self.input_path(day);
+        let res = runner::read_file(&input)
+            .map_err(|e| e.on_day(day))
+            .and_then(|i| runner::run_input(day, i, self.parts));
+        let results = [(day, res)];
+        let shown = output::render(&results, self.format);
+        let [(_, res)] = results;
+
+        self.runs.push_back(Run {
+            day,
+            input,
+            parts: self.parts,
+            res,
+        });
+        if self.runs.len() > HISTORY {
+            self.runs.pop_front();
+        }
+
+        shown
+    }
+
+    //the last n runs, most recent last
+    fn history(&self, n: usize) -> String {
+        if self.runs.is_empty() {
+            return String::from("Nothing has been run yet");
+        }
+
+        let header = ["#", "Day", "Input", "Part 1", "Part 2", "Overall"];
+        let first = self.runs.len().saturating_sub(n);
+        let rows: Vec<Vec<String>> = self
+            .runs
+            .iter()
+            .enumerate()
+            .skip(first)
+            .map(|(i, r)| {
+                let mut row = vec![(i + 1).to_string(), r.day.to_string(), r.input.clone()];
+                match &r.res {
+                    Ok(a) => row.extend([
+                        cell(a.part1(), r.parts.has(1)),
+                        cell(a.part2(), r.parts.has(2)),
+                        fmt_time(Some(a.overall_time())),
+                    ]),
+                    Err(e) => row.push(e.to_string()),
+                }
+                row
+            })
+            .collect();
+
+        table(&header, &rows, &[2, 3, 4])
+    }
+
+    //carry out one command, returning what to show, or none to quit
+    pub fn command(&mut self, line: &str) -> Option<String> {
+        let mut words = line.split_whitespace();
+        let (cmd, arg) = (words.next().unwrap_or(""), words.next());
+
+        let res = match (cmd, arg) {
+            ("quit" | "exit" | "q", _) => return None,
+            ("help" | "?", _) => String::from(HELP),
+            //a blank line re-runs the last selection
+            ("" | "run" | "r", _) => self.run(),
+            ("day" | "d", Some(d)) => self.select(d),
+            (d, None) if d.starts_with(|c: char| c.is_ascii_digit()) => self.select(d),
+            ("part" | "p", Some(p)) => match p.parse() {
+                Ok(p) => {
+                    self.parts = p;
+                    self.run()
+                }
+                Err(e) => e,
+            },
+            ("input" | "i", name) => {
+                self.source = Source::Own;
+                self.inputs.name = name.map(|n| n.to_string());
+                self.run()
+            }
+            ("file" | "f", Some(f)) if f != "-" => {
+                self.source = Source::File(f.to_string());
+                self.run()
+            }
+            ("dir", Some(d)) => {
+                self.inputs.dir = d.to_string();
+                self.run()
+            }
+            ("history" | "h", n) => match n.map(|n| n.parse::<usize>()) {
+                None => self.history(5),
+                Some(Ok(n)) => self.history(n),
+                Some(Err(_)) => String::from("Usage: history [number of runs]"),
+            },
+            _ => format!("Unknown command `{}`; type `help` for a list", line.trim()),
+        };
+
+        Some(res)
+    }
+
+    fn select(&mut self, d: &str) -> String {
+        match runner::parse_days(d) {
+            Ok(ds) if ds.len() == 1 && ds[0] <= DAYS.len() => {
+                self.day = Some(ds[0]);
+                self.run()
+            }
+            Ok(ds) if ds.len() == 1 => String::from("No solution for that day yet."),
+            Ok(_) => String::from("Pick a single day"),
+            Err(e) => e,
+        }
+    }
+}
+
+//Statics
+
+const HISTORY: usize = 100;
+
+const HELP: &str = "Commands:
+  <n>, day <n>      pick day n and run it
+  part 1|2|both     pick which parts to run, and run them
+  input [name]      use the day's own input, or a named one like `example`, and run it
+  file <path>       use the input file at path, and run it
+  dir <path>        look for inputs in another directory, and run
+  run, or Enter     run the current selection again
+  history [n]       show the last n runs (5 by default)
+  help              show this list
+  quit              leave";
+
+//Functions
+
+fn cell(r: Option<&str>, ran: bool) -> String {
+    match r.map(str::trim) {
+        Some(r) if r.contains('\n') => String::from("(multi-line)"),
+        Some(r) => r.to_string(),
+        None if ran => String::from("-"),
+        None => String::from("(skipped)"),
+    }
+}
+
+//read commands until quit or the end of input
+pub fn run(session: &mut Session, input: impl BufRead) {
+    println!("Type a day number to run it, or `help` for more commands");
+    print!("{}", session.prompt());
+    let _ = io::stdout().flush();
+
+    for line in input.lines() {
+        let Ok(line) = line else {
+            break;
+        };
+        match session.command(&line) {
+            Some(res) => println!("{}", res),
+            None => return,
+        }
+        print!("{}", session.prompt());
+        let _ = io::stdout().flush();
+    }
+    println!();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::runner::temp_inputs;
+    use std::fs;
+
+    //a session over a directory with day 1's example as its own input, and a smaller named one
+    fn session(name: &str) -> Session {
+        let inputs = temp_inputs(name);
+        fs::create_dir_all(&inputs.dir).unwrap();
+        fs::write(
+            inputs.path(1),
+            "199\n200\n208\n210\n200\n207\n240\n269\n260\n263\n",
+        )
+        .unwrap();
+        let small = Inputs {
+            name: Some(String::from("small")),
+            ..inputs.clone()
+        };
+        fs::write(small.path(1), "1\n2\n3\n4\n").unwrap();
+        Session::new(inputs, Parts::Both, Format::Text)
+    }
+
+    fn results(s: &mut Session, line: &str) -> Vec<String> {
+        s.command(line)
+            .unwrap()
+            .lines()
+            .filter(|l| l.starts_with("==") || l.contains("Result"))
+            .map(String::from)
+            .collect()
+    }
+
+    #[test]
+    fn selecting() {
+        let mut s = session("session-select");
+        assert_eq!(s.command("run").unwrap(), "Pick a day first, e.g. `day 5`");
+        assert_eq!(
+            results(&mut s, "1"),
+            vec![
+                "===========Day 1===========",
+                "Part 1 Result: 7",
+                "Part 2 Result: 5"
+            ]
+        );
+        assert_eq!(s.day, Some(1));
+        assert_eq!(results(&mut s, "part 2")[1], "Part 2 Result: 5");
+        assert!(results(&mut s, "").iter().all(|l| !l.contains("Part 1")));
+
+        //days out of range or without solutions keep the current one
+        assert_eq!(
+            s.command("day 25").unwrap(),
+            "No solution for that day yet."
+        );
+        assert_eq!(s.command("d 1-3").unwrap(), "Pick a single day");
+        assert_eq!(s.command("30").unwrap(), "Not a valid day >:[");
+        assert!(s.command("part 3").is_some());
+        assert_eq!((s.day, s.parts), (Some(1), Parts::Two));
+        let _ = fs::remove_dir_all(&s.inputs.dir);
+    }
+
+    #[test]
+    fn inputs() {
+        let mut s = session("session-inputs");
+        s.command("p 1");
+        s.command("1");
+        assert_eq!(results(&mut s, "input small")[1], "Part 1 Result: 3");
+        assert!(s.prompt().ends_with("day01.small.txt, part 1]> "));
+        assert_eq!(results(&mut s, "input")[1], "Part 1 Result: 7");
+
+        let (file, dir) = (s.inputs.path(1), s.inputs.dir.clone());
+        let missing = s.command("dir /nonexistent").unwrap();
+        assert!(missing.starts_with("Day 1 input error: Could not open /nonexistent/day01.txt"));
+        assert_eq!(
+            results(&mut s, &format!("file {}", file))[1],
+            "Part 1 Result: 7"
+        );
+        assert_eq!(s.source, Source::File(file));
+        let _ = fs::remove_dir_all(dir);
+    }
+
+    #[test]
+    fn history_and_quitting() {
+        let mut s = session("session-history");
+        assert_eq!(s.command("history").unwrap(), "Nothing has been run yet");
+        s.command("1");
+        s.command("part 1");
+        s.command("input small");
+
+        let h = s.command("h 2").unwrap();
+        let rows: Vec<&str> = h.lines().skip(2).collect();
+        assert_eq!(rows.len(), 2);
+        assert!(rows[0].starts_with("2 |") && rows[0].contains("day01.txt"));
+        assert!(rows[1].starts_with("3 |") && rows[1].contains("day01.small.txt"));
+        assert!(rows.iter().all(|r| r.contains("(skipped)")));
+        assert_eq!(s.runs.len(), 3);
+        assert_eq!(
+            s.command("history x").unwrap(),
+            "Usage: history [number of runs]"
+        );
+
+        assert_eq!(
+            s.command("frobnicate now").unwrap(),
+            "Unknown command `frobnicate now`; type `help` for a list"
+        );
+        assert!(s.command("help").unwrap().starts_with("Commands:"));
+        assert_eq!(s.command("quit"), None);
+        assert_eq!(s.command("q"), None);
+        let _ = fs::remove_dir_all(&s.inputs.dir);
+    }
+}
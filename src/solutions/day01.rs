@@ -1,40 +1,62 @@
-use crate::utils::{simple_parse, Answer};
+use crate::utils::{simple_parse, Day, SolveError};
+use std::fmt::Display;
 
-pub fn day01(input: String) -> Answer {
-    let mut answer = Answer::default();
+pub struct Day01;
+
+impl Day for Day01 {
+    type Parsed = Vec<u32>;
 
     //parse into list of ints
-    let vals = simple_parse::<u32>(input, "\n");
+    fn parse(input: String) -> Result<Vec<u32>, SolveError> {
+        let vals = simple_parse::<u32>(input, "\n")?;
+
+        if vals.len() < 3 {
+            return Err(SolveError::parse("Need at least three measurements"));
+        }
 
-    answer.record_parsed();
+        Ok(vals)
+    }
 
     //part 1: count all ascending steps
-    let mut p1_asc = 0;
-    let mut p1_last = vals[2];
+    fn part1(vals: &Vec<u32>) -> Result<impl Display, SolveError> {
+        Ok(vals.windows(2).filter(|w| w[0] < w[1]).count())
+    }
 
     //part 2: count all ascending windows of size 3
-    let mut p2_asc = 0;
-    let mut p2_last: u32 = vals[0..3].iter().sum();
-
-    //unroll first couple steps
-    p1_asc += if vals[0] < vals[1] { 1 } else { 0 };
-    p1_asc += if vals[1] < vals[2] { 1 } else { 0 };
-
-    for w in vals[1..].windows(3) {
-        let l = w.last().unwrap();
-        if l > &p1_last {
-            p1_asc += 1;
-        }
-
-        let s = w.iter().sum::<u32>();
-        if s > p2_last {
-            p2_asc += 1;
+    fn part2(vals: &Vec<u32>) -> Result<impl Display, SolveError> {
+        let mut asc = 0;
+        let mut last: u32 = vals[0..3].iter().sum();
+
+        for w in vals[1..].windows(3) {
+            let s = w.iter().sum::<u32>();
+            if s > last {
+                asc += 1;
+            }
+            last = s;
         }
 
-        p1_last = *l;
-        p2_last = s;
+        Ok(asc)
     }
-    answer.record_both(&p1_asc, &p2_asc);
+}
 
-    return answer;
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::runner::assert_example;
+
+    const EXAMPLE: &str = "199
+200
+208
+210
+200
+207
+240
+269
+260
+263";
+
+    #[test]
+    fn example() {
+        assert_example::<Day01>(EXAMPLE, Some("7"), Some("5"));
+    }
 }
@@ -1,60 +1,84 @@
-use crate::utils::Answer;
-use regex::Regex;
+use crate::utils::parse::{Pattern, Section};
+use crate::utils::{Day, SolveError};
+use std::fmt::Display;
 
-enum I {
+pub enum I {
     Up(u32),
     Down(u32),
     Forward(u32),
 }
 
-pub fn day02(input: String) -> Answer {
-    let mut answer = Answer::default();
+pub struct Day02;
+
+impl Day for Day02 {
+    type Parsed = Vec<I>;
 
     //parse instructions
-    let re = Regex::new(r"(\w+) (\d+)").unwrap();
-
-    let instrs: Vec<I> = re
-        .captures_iter(&input)
-        .map(|m| {
-            let n: u32 = m[2].parse().expect("Could not read number");
-            match &m[1] {
-                "forward" => I::Forward(n),
-                "up" => I::Up(n),
-                "down" => I::Down(n),
-                _ => panic!("Could not read instruction"),
+    fn parse(input: String) -> Result<Vec<I>, SolveError> {
+        let re = Pattern::new(r"(\w+) (\d+)", "forward|up|down n");
+
+        re.parse_lines(&Section::whole(&input), |f| {
+            let n: u32 = f.get(2)?;
+            match f.str(1) {
+                "forward" => Ok(I::Forward(n)),
+                "up" => Ok(I::Up(n)),
+                "down" => Ok(I::Down(n)),
+                x => Err(f.err(1, format!("Could not read instruction `{}`", x))),
             }
         })
-        .collect();
-
-    answer.record_parsed();
+    }
 
     //part 1: run instrs, multiply depth and distance
-    let mut p1_dist = 0;
-    let mut p1_depth = 0;
-    //part 2: include aim mechanic, meaning up and down only alter depth by aim on a forward command
-    let mut p2_dist = 0;
-    let mut p2_depth = 0;
-    let mut aim = 0;
-
-    for i in instrs {
-        match i {
-            I::Forward(n) => {
-                p1_dist += n;
-                p2_dist += n;
-                p2_depth += aim * n
-            }
-            I::Up(n) => {
-                p1_depth -= n;
-                aim -= n
+    fn part1(instrs: &Vec<I>) -> Result<impl Display, SolveError> {
+        let mut dist = 0;
+        let mut depth = 0;
+
+        for i in instrs {
+            match i {
+                I::Forward(n) => dist += n,
+                I::Up(n) => depth -= n,
+                I::Down(n) => depth += n,
             }
-            I::Down(n) => {
-                p1_depth += n;
-                aim += n
+        }
+
+        Ok(dist * depth)
+    }
+
+    //part 2: include aim mechanic, meaning up and down only alter depth by aim on a forward command
+    fn part2(instrs: &Vec<I>) -> Result<impl Display, SolveError> {
+        let mut dist = 0;
+        let mut depth = 0;
+        let mut aim = 0;
+
+        for i in instrs {
+            match i {
+                I::Forward(n) => {
+                    dist += n;
+                    depth += aim * n
+                }
+                I::Up(n) => aim -= n,
+                I::Down(n) => aim += n,
             }
         }
+
+        Ok(dist * depth)
     }
+}
 
-    answer.record_both(&(p1_dist * p1_depth), &(p2_dist * p2_depth));
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::runner::assert_example;
 
-    return answer;
+    const EXAMPLE: &str = "forward 5
+down 5
+forward 8
+up 3
+down 8
+forward 2";
+
+    #[test]
+    fn example() {
+        assert_example::<Day02>(EXAMPLE, Some("150"), Some("900"));
+    }
 }
@@ -1,6 +1,7 @@
-use crate::utils::Answer;
+use crate::utils::{Day, SolveError};
+use std::fmt::Display;
 
-fn bvec_to_u32(v: &Vec<u32>) -> u32 {
+fn bvec_to_u32(v: &[u32]) -> u32 {
     v.iter()
         .rev()
         .zip(0..v.len())
@@ -9,8 +10,8 @@ fn bvec_to_u32(v: &Vec<u32>) -> u32 {
 }
 
 //finds the commonmost element at an index in a series of bvecs
-fn find_commonmost(nums: &Vec<Vec<u32>>, i: usize) -> u32 {
-    let half: u32 = (nums.len() as u32 + 1) / 2;
+fn find_commonmost(nums: &[Vec<u32>], i: usize) -> u32 {
+    let half: u32 = (nums.len() as u32).div_ceil(2);
 
     let sum: u32 = nums.iter().map(|v| v[i]).sum();
 
@@ -26,7 +27,7 @@ fn find_by_criteria(criteria: fn(u32, u32) -> bool, mut nums: Vec<Vec<u32>>) ->
     for i in 0..nums[0].len() {
         let c = find_commonmost(&nums, i);
 
-        nums = nums.into_iter().filter(|v| criteria(v[i], c)).collect();
+        nums.retain(|v| criteria(v[i], c));
 
         if nums.len() == 1 {
             return nums[0].clone();
@@ -36,41 +37,83 @@ fn find_by_criteria(criteria: fn(u32, u32) -> bool, mut nums: Vec<Vec<u32>>) ->
     vec![]
 }
 
-pub fn day03(input: String) -> Answer {
-    let mut answer = Answer::default();
+pub struct Day03;
 
-    //parse into vec of lists of ints
-    let nums: Vec<Vec<u32>> = input
-        .lines()
-        .map(|l| {
-            l.chars()
-                .map(|c| c.to_digit(2).unwrap())
-                .collect::<Vec<u32>>()
-        })
-        .collect();
+impl Day for Day03 {
+    type Parsed = Vec<Vec<u32>>;
 
-    let bit_len = nums[0].len() as u32;
-    let half: u32 = nums.len() as u32 / 2;
+    //parse into vec of lists of ints
+    fn parse(input: String) -> Result<Vec<Vec<u32>>, SolveError> {
+        let nums: Vec<Vec<u32>> = input
+            .lines()
+            .enumerate()
+            .map(|(y, l)| {
+                l.chars()
+                    .enumerate()
+                    .map(|(x, c)| {
+                        c.to_digit(2).ok_or_else(|| {
+                            SolveError::parse(format!("{:?} is not a bit", c)).at(y + 1, x + 1)
+                        })
+                    })
+                    .collect::<Result<Vec<u32>, _>>()
+            })
+            .collect::<Result<_, _>>()?;
+
+        if nums.is_empty() || nums.iter().any(|n| n.len() != nums[0].len()) {
+            return Err(SolveError::parse(
+                "Expected a non-empty list of equal-length binary numbers",
+            ));
+        }
 
-    answer.record_parsed();
+        Ok(nums)
+    }
 
     //part 1: get modal result by first summing everything and then constructing final vector
-    let sums = nums.iter().fold(vec![0; bit_len as usize], |s, v| {
-        s.iter().zip(v.iter()).map(|(&a, &b)| a + b).collect()
-    });
+    fn part1(nums: &Vec<Vec<u32>>) -> Result<impl Display, SolveError> {
+        let bit_len = nums[0].len();
+        let half: u32 = nums.len() as u32 / 2;
 
-    let (gamma, epsilon): (Vec<u32>, Vec<u32>) = sums
-        .iter()
-        .map(|v| if v >= &half { (1, 0) } else { (0, 1) })
-        .unzip();
+        let sums = nums.iter().fold(vec![0; bit_len], |s, v| {
+            s.iter().zip(v.iter()).map(|(&a, &b)| a + b).collect()
+        });
 
-    answer.record(&(bvec_to_u32(&gamma) * bvec_to_u32(&epsilon)));
+        let (gamma, epsilon): (Vec<u32>, Vec<u32>) = sums
+            .iter()
+            .map(|v| if v >= &half { (1, 0) } else { (0, 1) })
+            .unzip();
+
+        Ok(bvec_to_u32(&gamma) * bvec_to_u32(&epsilon))
+    }
 
     //part 2: apply search criteria to find oxygen and co2 ratings
-    let oxygen = find_by_criteria(|a, b| a == b, nums.clone());
-    let co2 = find_by_criteria(|a, b| a != b, nums);
+    fn part2(nums: &Vec<Vec<u32>>) -> Result<impl Display, SolveError> {
+        let oxygen = find_by_criteria(|a, b| a == b, nums.clone());
+        let co2 = find_by_criteria(|a, b| a != b, nums.clone());
 
-    answer.record(&(bvec_to_u32(&oxygen) * bvec_to_u32(&co2)));
+        Ok(bvec_to_u32(&oxygen) * bvec_to_u32(&co2))
+    }
+}
 
-    return answer;
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::runner::assert_example;
+
+    const EXAMPLE: &str = "00100
+11110
+10110
+10111
+10101
+01111
+00111
+11100
+10000
+11001
+00010
+01010";
+
+    #[test]
+    fn example() {
+        assert_example::<Day03>(EXAMPLE, Some("198"), Some("230"));
+    }
 }
@@ -1,9 +1,10 @@
-use crate::utils::Answer;
-use regex::Regex;
+use crate::utils::parse::{list, sections, words};
+use crate::utils::{Day, Phase, SolveError};
 use std::collections::{HashMap, HashSet};
+use std::fmt::Display;
 
-#[derive(Debug)]
-struct Bingo {
+#[derive(Debug, Clone)]
+pub struct Bingo {
     row_counts: [u8; 5],
     col_counts: [u8; 5],
     num_positions: HashMap<u8, (u8, u8)>,
@@ -29,74 +30,115 @@ impl Bingo {
     }
 
     fn mark(&mut self, to_mark: u8) {
-        match self.num_positions.get(&to_mark) {
-            Some((x, y)) => {
-                self.unmarked.remove(&to_mark);
-                self.row_counts[*y as usize] += 1;
-                self.col_counts[*x as usize] += 1;
-            }
-            None => {}
+        if let Some((x, y)) = self.num_positions.get(&to_mark) {
+            self.unmarked.remove(&to_mark);
+            self.row_counts[*y as usize] += 1;
+            self.col_counts[*x as usize] += 1;
         }
     }
 }
 
-pub fn day04(input: String) -> Answer {
-    let mut answer = Answer::default();
-    let n_parse = Regex::new(r"(\d+)").unwrap();
-
-    //parse first line as sequence of calls, rest as bingo cards
-    let s_chunks: Vec<&str> = input.split("\n\n").collect();
-
-    let sequence: Vec<u8> = s_chunks[0].split(",").map(|c| c.parse().unwrap()).collect();
-
-    let mut sheets: Vec<Bingo> = s_chunks[1..]
-        .into_iter()
-        .map(|g| {
-            g
-                //get each line of a grid
-                .split("\n")
-                //assigning each a y positon
-                .zip(0..)
-                .map(|(l, y)| {
-                    //parse out each integer sequence
-                    n_parse
-                        .captures_iter(l)
-                        //assigning each an x position
-                        .zip(0..)
-                        //parse and format for collection into a HashMap
-                        .map(|(v, x)| (v[1].parse::<u8>().unwrap(), (x, y)))
-                        .collect::<Vec<(u8, (u8, u8))>>()
-                })
-                .flatten()
-                .collect()
-        })
-        .map(|m| Bingo::new(m))
-        .collect();
-
-    answer.record_parsed();
-
-    //part 1: get the sum of the unmarked spaces of the winning sheet times the last number
-    //part 2: get last score
+//play every card, returning the scores of each card in the order they win
+fn play(sequence: &[u8], mut sheets: Vec<Bingo>) -> Vec<u32> {
     let mut scores: Vec<u32> = vec![];
 
     for n in sequence {
-        sheets.iter_mut().for_each(|s| s.mark(n));
+        sheets.iter_mut().for_each(|s| s.mark(*n));
 
-        sheets.retain(|s| match (s.check(), scores.as_slice()) {
-            (Some(v), []) => {
-                scores.push(v * n as u32);
-                answer.record(&(v * n as u32));
-                false
-            }
-            (Some(v), _) => {
-                scores.push(v * n as u32);
+        sheets.retain(|s| match s.check() {
+            Some(v) => {
+                scores.push(v * *n as u32);
                 false
             }
-            _ => true,
+            None => true,
         });
     }
 
-    answer.record(scores.last().unwrap());
+    scores
+}
+
+pub struct Day04;
+
+impl Day for Day04 {
+    type Parsed = (Vec<u8>, Vec<Bingo>);
+
+    //parse first line as sequence of calls, rest as bingo cards
+    fn parse(input: String) -> Result<(Vec<u8>, Vec<Bingo>), SolveError> {
+        let s_chunks = sections(&input);
+        let (seq, cards) = s_chunks
+            .split_first()
+            .ok_or_else(|| SolveError::parse("Expected a sequence of numbers"))?;
+
+        let sequence: Vec<u8> = list(seq.text, ",", seq.start)?;
+
+        let mut sheets: Vec<Bingo> = vec![];
+
+        for g in cards {
+            let mut m = HashMap::new();
+
+            //get each line of a grid, assigning each a y position
+            for ((i, l), y) in g.lines().zip(0..) {
+                let row: Vec<u8> = words(l, i)?;
+                if row.len() != 5 || y > 4 {
+                    return Err(SolveError::parse("Bingo cards must be 5x5").at_line(i + 1));
+                }
+
+                //assign each number an x position
+                for (v, x) in row.into_iter().zip(0..) {
+                    m.insert(v, (x, y));
+                }
+            }
+
+            sheets.push(Bingo::new(m));
+        }
+
+        Ok((sequence, sheets))
+    }
+
+    //part 1: get the sum of the unmarked spaces of the winning sheet times the last number
+    fn part1((sequence, sheets): &(Vec<u8>, Vec<Bingo>)) -> Result<impl Display, SolveError> {
+        play(sequence, sheets.clone())
+            .first()
+            .copied()
+            .ok_or_else(|| SolveError::new(Phase::Part1, "No bingo card ever wins"))
+    }
+
+    //part 2: get last score
+    fn part2((sequence, sheets): &(Vec<u8>, Vec<Bingo>)) -> Result<impl Display, SolveError> {
+        play(sequence, sheets.clone())
+            .last()
+            .copied()
+            .ok_or_else(|| SolveError::new(Phase::Part2, "No bingo card ever wins"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::runner::assert_example;
+
+    const EXAMPLE: &str = "7,4,9,5,11,17,23,2,0,14,21,24,10,16,13,6,15,25,12,22,18,20,8,19,3,26,1
 
-    return answer;
+22 13 17 11  0
+ 8  2 23  4 24
+21  9 14 16  7
+ 6 10  3 18  5
+ 1 12 20 15 19
+
+ 3 15  0  2 22
+ 9 18 13 17  5
+19  8  7 25 23
+20 11 10 24  4
+14 21 16 12  6
+
+14 21 17 24  4
+10 16 15  9 19
+18  8 23 26 20
+22 11 13  6  5
+ 2  0 12  3  7";
+
+    #[test]
+    fn example() {
+        assert_example::<Day04>(EXAMPLE, Some("4512"), Some("1924"));
+    }
 }
@@ -1,7 +1,8 @@
-use crate::utils::{Answer, Grid, Pt2d};
-use regex::Regex;
+use crate::utils::parse::{Pattern, Section};
+use crate::utils::{Day, Grid, Pt2d, SolveError};
 use std::cmp::max;
 use std::collections::HashMap;
+use std::fmt::Display;
 
 fn diff(a: i32, b: i32) -> i32 {
     if a < b {
@@ -14,26 +15,49 @@ fn diff(a: i32, b: i32) -> i32 {
 }
 
 #[derive(Debug)]
-enum Line {
+pub enum Line {
     Diag(Vec<Pt2d>),
     Oth(Vec<Pt2d>),
 }
 
-pub fn day05(input: String) -> Answer {
-    let mut answer = Answer::default();
+//count the points covered by more than one of the given lines
+fn overlaps(lines: &[Line], diagonals: bool) -> usize {
+    let mut g = Grid::new(HashMap::new(), (0, 0), 0);
+
+    for l in lines {
+        match l {
+            Line::Diag(pts) if diagonals => g.updates(pts.to_vec(), |v| v + 1),
+            Line::Diag(_) => {}
+            Line::Oth(pts) => g.updates(pts.to_vec(), |v| v + 1),
+        }
+    }
+
+    g.grid.values().filter(|v| **v > 1).count()
+}
+
+pub struct Day05;
+
+impl Day for Day05 {
+    type Parsed = Vec<Line>;
 
     //parse coordinates into a series of points
     //and organise by diagonal and nondiagonal
-    let re = Regex::new(r"(\d+),(\d+) -> (\d+),(\d+)").unwrap();
-
-    let lines: Vec<Line> = input
-        .lines()
-        .map(|l| re.captures(l).unwrap())
-        .map(|c| {
-            let x1: i32 = c[1].parse().unwrap();
-            let y1: i32 = c[2].parse().unwrap();
-            let x2: i32 = c[3].parse().unwrap();
-            let y2: i32 = c[4].parse().unwrap();
+    fn parse(input: String) -> Result<Vec<Line>, SolveError> {
+        let re = Pattern::new(r"(\d+),(\d+) -> (\d+),(\d+)", "x1,y1 -> x2,y2");
+
+        re.parse_lines(&Section::whole(&input), |f| {
+            let x1: i32 = f.get(1)?;
+            let y1: i32 = f.get(2)?;
+            let x2: i32 = f.get(3)?;
+            let y2: i32 = f.get(4)?;
+
+            if x1 != x2 && y1 != y2 && i32::abs(x1 - x2) != i32::abs(y1 - y2) {
+                return Err(f.err(
+                    0,
+                    "Lines must be horizontal, vertical or diagonal at 45 degrees",
+                ));
+            }
+
             let dx = diff(x1, x2);
             let dy = diff(y1, y2);
             let mut pt = (x1, y1);
@@ -45,38 +69,42 @@ pub fn day05(input: String) -> Answer {
             }
 
             if (x1 == x2) || (y1 == y2) {
-                Line::Oth(pts)
+                Ok(Line::Oth(pts))
             } else {
-                Line::Diag(pts)
+                Ok(Line::Diag(pts))
             }
         })
-        .collect();
-
-    answer.record_parsed();
+    }
 
     //part 1: find overlapping straight line points
-    let mut g = Grid::new(HashMap::new(), (0, 0), 0);
-
-    for l in &lines {
-        match l {
-            Line::Diag(_) => {}
-            Line::Oth(pts) => g.updates(pts.to_vec(), |v| v + 1),
-        }
+    fn part1(lines: &Vec<Line>) -> Result<impl Display, SolveError> {
+        Ok(overlaps(lines, false))
     }
 
-    let p1 = g.grid.values().filter(|v| **v > 1).count();
-    answer.record(&p1);
-
     //part 2: find overlapping for all
-    for l in lines {
-        match l {
-            Line::Oth(_) => {}
-            Line::Diag(pts) => g.updates(pts, |v| v + 1),
-        }
+    fn part2(lines: &Vec<Line>) -> Result<impl Display, SolveError> {
+        Ok(overlaps(lines, true))
     }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::runner::assert_example;
 
-    let p2 = g.grid.values().filter(|v| **v > 1).count();
-    answer.record(&p2);
+    const EXAMPLE: &str = "0,9 -> 5,9
+8,0 -> 0,8
+9,4 -> 3,4
+2,2 -> 2,1
+7,0 -> 7,4
+6,4 -> 2,0
+0,9 -> 2,9
+3,4 -> 1,4
+0,0 -> 8,8
+5,5 -> 8,2";
 
-    return answer;
+    #[test]
+    fn example() {
+        assert_example::<Day05>(EXAMPLE, Some("5"), Some("12"));
+    }
 }
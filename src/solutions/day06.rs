@@ -1,7 +1,8 @@
-use crate::utils::{simple_parse, Answer};
+use crate::utils::{simple_parse, Day, SolveError};
 use std::collections::VecDeque;
+use std::fmt::Display;
 
-fn day_passes<'a>(mut population: VecDeque<usize>) -> VecDeque<usize> {
+fn day_passes(mut population: VecDeque<usize>) -> VecDeque<usize> {
     //we get the population of day 0 fish, which would be the first in the list
     //we can then 'reset' them by adding them to day 6 fish, then appending their spawn
     //at the end of our queue
@@ -12,30 +13,55 @@ fn day_passes<'a>(mut population: VecDeque<usize>) -> VecDeque<usize> {
     population
 }
 
-pub fn day06(input: String) -> Answer {
-    let mut answer = Answer::default();
+//total population after some number of days
+fn proliferate(mut pops: VecDeque<usize>, days: usize) -> usize {
+    for _ in 0..days {
+        pops = day_passes(pops)
+    }
+
+    pops.iter().sum()
+}
+
+pub struct Day06;
+
+impl Day for Day06 {
+    type Parsed = VecDeque<usize>;
 
     //parse input into populations by age
-    let nums = simple_parse::<u8>(input, ",");
-    let mut pops: VecDeque<usize> = (0..9)
-        .map(|v| nums.iter().filter(|x| **x == v).count())
-        .collect();
+    fn parse(input: String) -> Result<VecDeque<usize>, SolveError> {
+        let nums = simple_parse::<u8>(input, ",")?;
 
-    answer.record_parsed();
+        Ok((0..9)
+            .map(|v| nums.iter().filter(|x| **x == v).count())
+            .collect())
+    }
 
     //part 1: proliferation for 80 days
-    for _ in 0..80 {
-        pops = day_passes(pops)
+    fn part1(pops: &VecDeque<usize>) -> Result<impl Display, SolveError> {
+        Ok(proliferate(pops.clone(), 80))
     }
 
-    answer.record(&pops.iter().sum::<usize>());
-
     //part 2: further proliferation
-    for _ in 0..(256 - 80) {
-        pops = day_passes(pops)
+    fn part2(pops: &VecDeque<usize>) -> Result<impl Display, SolveError> {
+        Ok(proliferate(pops.clone(), 256))
     }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::runner::assert_example;
 
-    answer.record(&pops.iter().sum::<usize>());
+    const EXAMPLE: &str = "3,4,3,1,2";
 
-    return answer;
+    #[test]
+    fn example() {
+        assert_example::<Day06>(EXAMPLE, Some("5934"), Some("26984457539"));
+    }
+
+    #[test]
+    fn eighteen_days() {
+        let pops = Day06::parse(EXAMPLE.to_string()).unwrap();
+        assert_eq!(proliferate(pops, 18), 26);
+    }
 }
@@ -1,43 +1,53 @@
-use crate::utils::{simple_parse, Answer};
+use crate::utils::{simple_parse, Day, SolveError};
+use std::fmt::Display;
 
-pub fn day07(input: String) -> Answer {
-    let mut answer = Answer::default();
-
-    //parse
-    let mut locs = simple_parse::<i32>(input, ",");
-    locs.sort();
-
-    answer.record_parsed();
-
-    //part 1: minimum modification needed to align all elements
+//minimum total fuel to align all crabs, given the fuel cost of moving n steps
+fn min_fuel(locs: &[i32], cost: fn(i32) -> i32) -> i32 {
     let crab_max = locs.last().unwrap();
     let crab_min = locs[0];
 
-    let mut min_fuel = std::i32::MAX;
+    let mut min_fuel = i32::MAX;
 
     for mid in crab_min..*crab_max {
-        min_fuel = i32::min(min_fuel, locs.iter().map(|x| i32::abs(x - mid)).sum());
+        min_fuel = i32::min(min_fuel, locs.iter().map(|x| cost(i32::abs(x - mid))).sum());
     }
 
-    answer.record(&min_fuel);
+    min_fuel
+}
 
-    //part 2: minimum when distance accumulates more fuel usage
+pub struct Day07;
 
-    min_fuel = std::i32::MAX;
+impl Day for Day07 {
+    type Parsed = Vec<i32>;
 
-    for mid in crab_min..*crab_max {
-        min_fuel = i32::min(
-            min_fuel,
-            locs.iter()
-                .map(|x| {
-                    let n = i32::abs(x - mid);
-                    n * (n + 1) / 2
-                })
-                .sum(),
-        );
+    //parse
+    fn parse(input: String) -> Result<Vec<i32>, SolveError> {
+        let mut locs = simple_parse::<i32>(input, ",")?;
+        locs.sort();
+
+        Ok(locs)
     }
 
-    answer.record(&min_fuel);
+    //part 1: minimum modification needed to align all elements
+    fn part1(locs: &Vec<i32>) -> Result<impl Display, SolveError> {
+        Ok(min_fuel(locs, |n| n))
+    }
 
-    return answer;
+    //part 2: minimum when distance accumulates more fuel usage
+    fn part2(locs: &Vec<i32>) -> Result<impl Display, SolveError> {
+        Ok(min_fuel(locs, |n| n * (n + 1) / 2))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::runner::assert_example;
+
+    const EXAMPLE: &str = "16,1,2,0,4,2,7,1,2,14";
+
+    #[test]
+    fn example() {
+        assert_example::<Day07>(EXAMPLE, Some("37"), Some("168"));
+    }
 }
@@ -1,69 +1,126 @@
-use crate::utils::Answer;
+use crate::utils::parse::{Pattern, Section};
+use crate::utils::{Day, SolveError};
+use std::fmt::Display;
 
-type Disp<'a> = (Vec<&'a str>, Vec<&'a str>);
+type Disp = (Vec<String>, Vec<String>);
 
-pub fn day08(input: String) -> Answer {
-    let mut answer = Answer::default();
+pub struct Day08;
 
-    //parse
-    let patterns: Vec<Disp> = input
-        .lines()
-        .map(|l| {
-            let x: Vec<&str> = l.split(" ").collect();
-            (x[0..10].to_owned(), x[11..].to_owned())
-        })
-        .collect();
+impl Day for Day08 {
+    type Parsed = Vec<Disp>;
 
-    answer.record_parsed();
+    //parse: ten patterns, `|`, then four output digits per line
+    fn parse(input: String) -> Result<Vec<Disp>, SolveError> {
+        let re = Pattern::new(
+            r"((?:[a-g]+ ){10})\| ((?:[a-g]+ ){3}[a-g]+)",
+            "<ten patterns> | <four digits>",
+        );
+        let words = |s: &str| s.split_whitespace().map(String::from).collect();
+
+        re.parse_lines(&Section::whole(&input), |f| {
+            Ok((words(f.str(1)), words(f.str(2))))
+        })
+    }
 
     //part 1 identify and count (1,4,7,8)  occurrences in right side
-    let mut acc = 0;
+    fn part1(patterns: &Vec<Disp>) -> Result<impl Display, SolveError> {
+        let mut acc = 0;
 
-    for l in patterns.iter() {
-        acc += (l.1)
-            .iter()
-            .filter(|e| e.len() != 5 && e.len() != 6)
-            .count()
-    }
+        for l in patterns.iter() {
+            acc += (l.1)
+                .iter()
+                .filter(|e| e.len() != 5 && e.len() != 6)
+                .count()
+        }
 
-    answer.record(&acc);
+        Ok(acc)
+    }
 
     //part 2: identify numbers by comparing against overlap with unique-sized numbers
     //1,7,4,8 are of known size, other numbers have subsections of them
-    let mut sum: u32 = 0;
-
-    for mut l in patterns {
-        l.0.sort_by(|a, b| a.len().partial_cmp(&b.len()).unwrap());
-        let mut res = "".to_owned();
-        //since we know for certain where one and four will be in the list when we've sorted it by length,
-        //we can use them as comparators for other numbers with similar segments
-        let one: Vec<char> = l.0[0].chars().collect();
-        let four: Vec<char> = l.0[2].chars().collect();
-
-        for c in l.1 {
-            res.push_str(
-                match (
-                    c.len(),
-                    c.chars().filter(|x| four.contains(x)).count(), //compare against 4
-                    c.chars().filter(|x| one.contains(x)).count(),  //compare against 1
-                ) {
-                    (6, 3, 1) => "6",
-                    (6, 3, 2) => "0",
-                    (6, 4, _) => "9",
-                    (5, 3, 1) => "5",
-                    (5, 3, 2) => "3",
-                    (5, 2, _) => "2",
-                    (2, _, _) => "1",
-                    (3, _, _) => "7",
-                    (4, _, _) => "4",
-                    _ => "8",
-                },
-            )
+    fn part2(patterns: &Vec<Disp>) -> Result<impl Display, SolveError> {
+        let mut sum: u32 = 0;
+
+        for l in patterns {
+            let mut sorted = l.0.clone();
+            sorted.sort_by(|a, b| a.len().partial_cmp(&b.len()).unwrap());
+            let mut res = 0;
+            //since we know for certain where one and four will be in the list when we've sorted it by length,
+            //we can use them as comparators for other numbers with similar segments
+            let one: Vec<char> = sorted[0].chars().collect();
+            let four: Vec<char> = sorted[2].chars().collect();
+
+            for c in &l.1 {
+                res = res * 10
+                    + match (
+                        c.len(),
+                        c.chars().filter(|x| four.contains(x)).count(), //compare against 4
+                        c.chars().filter(|x| one.contains(x)).count(),  //compare against 1
+                    ) {
+                        (6, 3, 1) => 6,
+                        (6, 3, 2) => 0,
+                        (6, 4, _) => 9,
+                        (5, 3, 1) => 5,
+                        (5, 3, 2) => 3,
+                        (5, 2, _) => 2,
+                        (2, _, _) => 1,
+                        (3, _, _) => 7,
+                        (4, _, _) => 4,
+                        _ => 8,
+                    }
+            }
+            sum += res;
         }
-        sum += res.parse::<u32>().unwrap();
+
+        Ok(sum)
     }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::runner::assert_example;
+
+    const EXAMPLE: &str =
+        "be cfbegad cbdgef fgaecd cgeb fdcge agebfd fecdb fabcd edb | fdgacbe cefdb cefbgd gcbe
+edbfga begcd cbg gc gcadebf fbgde acbgfd abcde gfcbed gfec | fcgedb cgb dgebacf gc
+fgaebd cg bdaec gdafb agbcfd gdcbef bgcad gfac gcb cdgabef | cg cg fdcagb cbg
+fbegcd cbd adcefb dageb afcb bc aefdc ecdab fgdeca fcdbega | efabcd cedba gadfec cb
+aecbfdg fbg gf bafeg dbefa fcge gcbea fcaegb dgceab fcbdga | gecf egdcabf bgf bfgea
+fgeab ca afcebg bdacfeg cfaedg gcfdb baec bfadeg bafgc acf | gebdcfa ecba ca fadegcb
+dbcfg fgd bdegcaf fgec aegbdf ecdfab fbedc dacgb gdcebf gf | cefg dcbef fcge gbcadfe
+bdfegc cbegaf gecbf dfcage bdacg ed bedf ced adcbefg gebcd | ed bcgafe cdgba cbgef
+egadfb cdbfeg cegd fecab cgb gbdefca cg fgcdab egfdb bfceg | gbdfcae bgc cg cgb
+gcafb gcf dcaebfg ecagb gf abcdeg gaef cafbge fdbac fegbdc | fgae cfgab fg bagce";
 
-    answer.record(&sum);
+    const SINGLE: &str =
+        "acedgfb cdfbe gcdfa fbcad dab cefabd cdfgeb eafb cagedb ab | cdfeb fcadb cdfeb cdbaf";
+
+    #[test]
+    fn example() {
+        assert_example::<Day08>(EXAMPLE, Some("26"), Some("61229"));
+    }
 
-    return answer;
+    #[test]
+    fn single_entry() {
+        assert_example::<Day08>(SINGLE, Some("0"), Some("5353"));
+    }
+
+    //missing or extra output digits are rejected where they're found, rather than panicking in part 2
+    #[test]
+    fn bad_entries() {
+        let (patterns, _) = SINGLE.split_once(" | ").unwrap();
+        let cases = [
+            (format!("{}\n{} |", SINGLE, patterns), 2),
+            (format!("{} ab ab ab ab ab ab", SINGLE), 1),
+            (format!("{}\n{}", SINGLE, patterns), 2),
+        ];
+        for (input, line) in cases {
+            let e = Day08::parse(input).err().unwrap();
+            assert_eq!((e.line, e.col), (Some(line), Some(1)));
+            assert!(e
+                .msg
+                .starts_with("Expected `<ten patterns> | <four digits>`"));
+        }
+    }
 }
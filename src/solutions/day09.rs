@@ -1,51 +1,77 @@
-use crate::utils::{Answer, Grid, Point, Pt2d};
+use crate::utils::parse::{dense_grid, Section};
+use crate::utils::{Day, DenseGrid, Phase, Point, Pt2d, SolveError};
+use std::fmt::Display;
 
-fn compare_to_ns(g: &Grid<Pt2d, u8>, p: Pt2d, cmp: fn(u8, u8) -> bool) -> bool {
-    let v = g.grid.get(&p).unwrap();
+fn compare_to_ns(g: &DenseGrid<u8>, p: Pt2d, cmp: fn(u8, u8) -> bool) -> bool {
+    let v = g.get(&p).unwrap();
     let ns = p.neighbours_card();
 
-    ns.iter()
-        .filter_map(|n| g.grid.get(&n))
-        .all(|nv| cmp(*v, *nv))
+    ns.iter().filter_map(|n| g.get(n)).all(|nv| cmp(v, nv))
 }
 
-pub fn day09(input: String) -> Answer {
-    let mut answer = Answer::default();
+//find all lowest points
+fn lowests(g: &DenseGrid<u8>) -> Vec<Pt2d> {
+    g.keys()
+        .filter(|k| compare_to_ns(g, *k, |x, y| x < y))
+        .collect()
+}
 
-    //parse into 2d grid
-    let g = Grid::from(input, 9, |c| c.to_digit(10).unwrap() as u8);
+pub struct Day09;
 
-    answer.record_parsed();
+impl Day for Day09 {
+    type Parsed = DenseGrid<u8>;
 
-    //part 1: find all lowest points, summing their depths +1
-    //we retain the lowest points for use in part 2
-    let lowests: Vec<Pt2d> = g
-        .grid
-        .keys()
-        .filter(|k| compare_to_ns(&g, **k, |x, y| x < y))
-        .cloned()
-        .collect();
-
-    let p1: u32 = lowests
-        .iter()
-        .map(|k| (g.grid.get(k).unwrap() + 1) as u32)
-        .sum();
+    //parse into 2d grid
+    fn parse(input: String) -> Result<DenseGrid<u8>, SolveError> {
+        dense_grid(&Section::whole(&input), 9, |c| {
+            c.to_digit(10).map(|d| d as u8)
+        })
+        .map(|(g, _)| g)
+    }
 
-    answer.record(&p1);
+    //part 1: find all lowest points, summing their depths +1
+    fn part1(g: &DenseGrid<u8>) -> Result<impl Display, SolveError> {
+        Ok(lowests(g)
+            .iter()
+            .map(|k| (g.get_def(k) + 1) as u32)
+            .sum::<u32>())
+    }
 
     //part 2: multiply the size of the three largest basins
     //very lenient puzzle, since we are guaranteed each lowest point belonging to one solitary basin with no overlap!
-    let mut basin_sizes: Vec<usize> = lowests
-        .iter()
-        .map(|l| g.flood_find(*l, |x| x < 9).len())
-        .collect();
-    //sort in descending order
-    basin_sizes.sort();
-    basin_sizes.reverse();
+    fn part2(g: &DenseGrid<u8>) -> Result<impl Display, SolveError> {
+        let mut basin_sizes: Vec<usize> = lowests(g)
+            .iter()
+            .map(|l| g.flood_find(*l, |x| x < 9).len())
+            .collect();
+        //sort in descending order
+        basin_sizes.sort();
+        basin_sizes.reverse();
+
+        if basin_sizes.len() < 3 {
+            return Err(SolveError::new(
+                Phase::Part2,
+                "Found fewer than three basins",
+            ));
+        }
+
+        Ok(basin_sizes[0..3].iter().product::<usize>())
+    }
+}
 
-    let p2: usize = basin_sizes[0..3].iter().product();
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::runner::assert_example;
 
-    answer.record(&p2);
+    const EXAMPLE: &str = "2199943210
+3987894921
+9856789892
+8767896789
+9899965678";
 
-    return answer;
+    #[test]
+    fn example() {
+        assert_example::<Day09>(EXAMPLE, Some("15"), Some("1134"));
+    }
 }
@@ -1,11 +1,13 @@
-use crate::utils::Answer;
+use crate::utils::{Day, Phase, SolveError};
 use std::collections::VecDeque;
+use std::fmt::Display;
 
 const OPENS: [char; 4] = ['[', '<', '{', '('];
+const CLOSES: [char; 4] = [']', '>', '}', ')'];
 const MATCHES: [(char, char); 4] = [('[', ']'), ('<', '>'), ('(', ')'), ('{', '}')];
 
 #[derive(Debug, Clone)]
-enum B {
+pub enum B {
     Open(char),
     Close(char),
 }
@@ -59,57 +61,95 @@ fn validate_line(stack: &mut Vec<B>, next: B) -> Option<u32> {
     }
 }
 
-pub fn day10(input: String) -> Answer {
-    let mut answer = Answer::default();
+//score the first corrupt char on a line, or return the unclosed stack if there isn't one
+fn check_line(l: &VecDeque<B>) -> Result<Vec<B>, u32> {
+    let mut stack: Vec<B> = vec![];
+    let mut queue = l.clone();
 
-    //parse into character vectors
-    let lines: Vec<VecDeque<B>> = input
-        .lines()
-        .map(|l| {
-            l.chars()
-                .map(|c| {
-                    if OPENS.contains(&c) {
-                        B::Open(c)
-                    } else {
-                        B::Close(c)
-                    }
-                })
-                .collect()
-        })
-        .collect();
-
-    answer.record_parsed();
-
-    //part 1: record and score the first corrupt char on each line (retain valid queues for part 2)
-    let mut score1: u32 = 0;
-    let mut retained: Vec<Vec<B>> = vec![];
-    for l in lines {
-        let mut stack: Vec<B> = vec![];
-        let mut res: Option<u32> = None;
-        let mut queue = l.clone();
-
-        while queue.len() > 0 && res.is_none() {
-            res = validate_line(&mut stack, queue.pop_front().unwrap());
-        }
-
-        match res {
-            Some(v) => score1 += v,
-            None => retained.push(stack),
+    while let Some(next) = queue.pop_front() {
+        if let Some(v) = validate_line(&mut stack, next) {
+            return Err(v);
         }
     }
 
-    answer.record(&score1);
+    Ok(stack)
+}
+
+pub struct Day10;
+
+impl Day for Day10 {
+    type Parsed = Vec<VecDeque<B>>;
+
+    //parse into character vectors
+    fn parse(input: String) -> Result<Vec<VecDeque<B>>, SolveError> {
+        input
+            .lines()
+            .enumerate()
+            .map(|(y, l)| {
+                l.chars()
+                    .enumerate()
+                    .map(|(x, c)| {
+                        if OPENS.contains(&c) {
+                            Ok(B::Open(c))
+                        } else if CLOSES.contains(&c) {
+                            Ok(B::Close(c))
+                        } else {
+                            Err(SolveError::parse(format!("Invalid symbol {:?}", c))
+                                .at(y + 1, x + 1))
+                        }
+                    })
+                    .collect()
+            })
+            .collect()
+    }
 
-    //part 2: get autocomplete score, record middle-most value
-    let mid: usize = retained.len() / 2;
-    let mut scores: Vec<_> = retained
-        .into_iter()
-        .map(|r| score_autocomplete(r))
-        .collect();
+    //part 1: score the first corrupt char on each line
+    fn part1(lines: &Vec<VecDeque<B>>) -> Result<impl Display, SolveError> {
+        Ok(lines
+            .iter()
+            .filter_map(|l| check_line(l).err())
+            .sum::<u32>())
+    }
 
-    scores.sort();
+    //part 2: get autocomplete score of the valid lines, record middle-most value
+    fn part2(lines: &Vec<VecDeque<B>>) -> Result<impl Display, SolveError> {
+        let mut scores: Vec<_> = lines
+            .iter()
+            .filter_map(|l| check_line(l).ok())
+            .map(score_autocomplete)
+            .collect();
+
+        scores.sort();
+
+        if scores.is_empty() {
+            return Err(SolveError::new(
+                Phase::Part2,
+                "No incomplete lines to autocomplete",
+            ));
+        }
 
-    answer.record(&scores[mid]);
+        Ok(scores[scores.len() / 2])
+    }
+}
 
-    answer
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::runner::assert_example;
+
+    const EXAMPLE: &str = "[({(<(())[]>[[{[]{<()<>>
+[(()[<>])]({[<{<<[]>>(
+{([(<{}[<>[]}>{[]{[(<()>
+(((({<>}<{<{<>}{[]{[]{}
+[[<[([]))<([[{}[[()]]]
+[{[{({}]{}}([{[{{{}}([]
+{<[[]]>}<{[{[{[]{()[[[]
+[<(<(<(<{}))><([]([]()
+<{([([[(<>()){}]>(<<{{
+<{([{{}}[<[[[<>{}]]]>[]]";
+
+    #[test]
+    fn example() {
+        assert_example::<Day10>(EXAMPLE, Some("26397"), Some("288957"));
+    }
 }
@@ -1,83 +1,61 @@
-use crate::utils::{Answer, Grid, Point, Pt2d};
-
-fn find_bursting(g: &Grid<Pt2d, u8>) -> Vec<Pt2d> {
-    g.grid
-        .iter()
-        .filter(|(_, v)| **v > 9)
-        .map(|(k, _)| *k)
-        .collect()
-}
-
-//step grid and return bursting points
-fn step_grid(g: &mut Grid<Pt2d, u8>) {
-    g.updates(g.grid.keys().cloned().collect(), |x| x + 1);
+use crate::utils::automaton::CellularAutomaton;
+use crate::utils::parse::{dense_grid, Section};
+use crate::utils::{Day, DenseGrid, Point, Pt2d, SolveError};
+use std::fmt::Display;
+
+type Octopuses = CellularAutomaton<Pt2d, u8, DenseGrid<u8>>;
+
+//everything gains energy, then anything above 9 flashes, giving its neighbours energy and resetting to 0.
+//returns how many flashed
+fn do_step(o: &mut Octopuses) -> usize {
+    o.step(|v, _| v + 1);
+    o.cascade(|v| v > 9, |v| v + 1, |_| 0).len()
 }
 
-//perform bursts by setting burst points to 0 and incrementing neighbours
-fn do_bursts(g: &mut Grid<Pt2d, u8>, bursting: Vec<Pt2d>) {
-    let ns: Vec<Pt2d> = bursting
-        .iter()
-        .map(|b| b.neighbours_all())
-        .flatten()
-        .filter(|n| g.grid.contains_key(n))
-        .collect();
-
-    g.updates(bursting, |_| 0);
-
-    g.updates(ns, |x| x + 1)
-}
-
-//returns (burst_num,burst_history length)
-fn do_step(g: &mut Grid<Pt2d, u8>) -> (usize, usize) {
-    step_grid(g);
-
-    let mut burst_history = vec![];
-    let mut bursts = 0;
-
-    loop {
-        let bursting = find_bursting(&g);
-        if bursting.len() == 0 {
-            break;
-        }
-
-        bursts += bursting.len();
-        burst_history.extend(bursting.clone());
-
-        do_bursts(g, bursting);
-    }
-
-    g.updates(burst_history.clone(), |_| 0);
-
-    (bursts, burst_history.len())
-}
+pub struct Day11;
 
-pub fn day11(input: String) -> Answer {
-    let mut answer = Answer::default();
+impl Day for Day11 {
+    type Parsed = DenseGrid<u8>;
 
     //parse into grid
-    let mut g = Grid::from(input, 9, |c| c.to_digit(10).unwrap() as u8);
-
-    answer.record_parsed();
+    fn parse(input: String) -> Result<DenseGrid<u8>, SolveError> {
+        dense_grid(&Section::whole(&input), 9, |c| {
+            c.to_digit(10).map(|d| d as u8)
+        })
+        .map(|(g, _)| g)
+    }
 
     //part 1 100 iterations
-    let mut bursts = 0;
-    for _ in 0..100 {
-        let (b, _) = do_step(&mut g);
-
-        bursts += b;
+    fn part1(g: &DenseGrid<u8>) -> Result<impl Display, SolveError> {
+        let mut o = Octopuses::bounded(g.clone(), Point::neighbours_all);
+        Ok((0..100).map(|_| do_step(&mut o)).sum::<usize>())
     }
 
-    answer.record(&bursts);
-
     //part 2: find step where everything bursts at once
-    for s in 101.. {
-        let (_, h) = do_step(&mut g);
-
-        if h == 100 {
-            answer.record(&s);
-            break;
-        }
+    fn part2(g: &DenseGrid<u8>) -> Result<impl Display, SolveError> {
+        let mut o = Octopuses::bounded(g.clone(), Point::neighbours_all);
+        Ok((1..).find(|_| do_step(&mut o) == g.len()).unwrap())
     }
+}
 
-    answer
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::runner::assert_example;
+
+    const EXAMPLE: &str = "5483143223
+2745854711
+5264556173
+6141336146
+6357385478
+4167524645
+2176841721
+6882881134
+4846848554
+5283751526";
+
+    #[test]
+    fn example() {
+        assert_example::<Day11>(EXAMPLE, Some("1656"), Some("195"));
+    }
 }
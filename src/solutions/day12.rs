@@ -1,18 +1,19 @@
-use crate::utils::Answer;
-use regex::Regex;
+use crate::utils::parse::{Pattern, Section};
+use crate::utils::{Day, SolveError};
 use std::cmp::PartialEq;
 use std::collections::{HashMap, HashSet};
+use std::fmt::Display;
 use std::hash::Hash;
 
 #[derive(Hash, PartialEq, Eq, Clone, Debug)]
-enum Node {
+pub enum Node {
     Start,
     End,
     Small(String),
     Big(String),
 }
 
-fn to_node<'a>(s: &'a str) -> Node {
+fn to_node(s: &str) -> Node {
     match (s, s.to_uppercase().eq(s)) {
         ("start", _) => Node::Start,
         ("end", _) => Node::End,
@@ -21,7 +22,7 @@ fn to_node<'a>(s: &'a str) -> Node {
     }
 }
 
-type Adjs = HashMap<Node, HashSet<Node>>;
+pub type Adjs = HashMap<Node, HashSet<Node>>;
 
 //recursively find paths to end from given node
 //if mulligan is available, ignore one instance of a repeated visit in a small cave
@@ -29,8 +30,8 @@ fn find_paths(adjs: &Adjs, mut mulligan: bool, mut visited: HashSet<Node>, from:
     match &from {
         Node::End => return 1,
         Node::Big(_) => (),
-        n @ _ => {
-            if visited.contains(&n) {
+        n => {
+            if visited.contains(n) {
                 if !mulligan {
                     return 0;
                 } else {
@@ -49,31 +50,98 @@ fn find_paths(adjs: &Adjs, mut mulligan: bool, mut visited: HashSet<Node>, from:
         .sum()
 }
 
-pub fn day12(input: String) -> Answer {
-    let mut answer = Answer::default();
+pub struct Day12;
+
+impl Day for Day12 {
+    type Parsed = Adjs;
 
     //parse into a hashmap of adjacencies
-    let re = Regex::new(r"(\w+)-(\w+)").unwrap();
-    let mut adjs: Adjs = HashMap::new();
-
-    for c in re.captures_iter(&input) {
-        let x = to_node(&c[1]);
-        let y = to_node(&c[2]);
-        adjs.entry(x.clone())
-            .or_insert(HashSet::new())
-            .insert(y.clone());
-        adjs.entry(y).or_insert(HashSet::new()).insert(x.clone());
-    }
+    fn parse(input: String) -> Result<Adjs, SolveError> {
+        let re = Pattern::new(r"(\w+)-(\w+)", "cave-cave");
+        let mut adjs: Adjs = HashMap::new();
+
+        let edges = re.parse_lines(&Section::whole(&input), |f| {
+            Ok((to_node(f.str(1)), to_node(f.str(2))))
+        })?;
+
+        for (x, y) in edges {
+            adjs.entry(x.clone()).or_default().insert(y.clone());
+            adjs.entry(y).or_default().insert(x.clone());
+        }
 
-    answer.record_parsed();
+        if !adjs.contains_key(&Node::Start) || !adjs.contains_key(&Node::End) {
+            return Err(SolveError::parse("Cave system needs a start and an end"));
+        }
+
+        Ok(adjs)
+    }
 
     //part 1: enumerate paths from start to end
-    let p1 = find_paths(&adjs, false, HashSet::new(), Node::Start);
-    answer.record(&p1);
+    fn part1(adjs: &Adjs) -> Result<impl Display, SolveError> {
+        Ok(find_paths(adjs, false, HashSet::new(), Node::Start))
+    }
 
     //part 2: enumerate paths from start to end with one repeat visit to a small cave allowed
-    let p2 = find_paths(&adjs, true, HashSet::new(), Node::Start);
-    answer.record(&p2);
+    fn part2(adjs: &Adjs) -> Result<impl Display, SolveError> {
+        Ok(find_paths(adjs, true, HashSet::new(), Node::Start))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::runner::assert_example;
+
+    const SMALL: &str = "start-A
+start-b
+A-c
+A-b
+b-d
+A-end
+b-end";
+
+    const MEDIUM: &str = "dc-end
+HN-start
+start-kj
+dc-start
+dc-HN
+LN-dc
+HN-end
+kj-sa
+kj-HN
+kj-dc";
+
+    const LARGE: &str = "fs-end
+he-DX
+fs-he
+start-DX
+pj-DX
+end-zg
+zg-sl
+zg-pj
+pj-he
+RW-he
+fs-DX
+pj-RW
+zg-RW
+start-pj
+he-WI
+zg-he
+pj-fs
+start-RW";
 
-    answer
+    #[test]
+    fn small() {
+        assert_example::<Day12>(SMALL, Some("10"), Some("36"));
+    }
+
+    #[test]
+    fn medium() {
+        assert_example::<Day12>(MEDIUM, Some("19"), Some("103"));
+    }
+
+    #[test]
+    fn large() {
+        assert_example::<Day12>(LARGE, Some("226"), Some("3509"));
+    }
 }
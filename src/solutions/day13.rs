@@ -1,74 +1,122 @@
-use crate::utils::{Answer, Grid, Pt2d};
-use regex::Regex;
+use crate::utils::parse::{split_sections, Pattern};
+use crate::utils::{letters, Axis, Day, Grid, Phase, Pt2d, SolveError};
 use std::collections::HashMap;
+use std::fmt::Display;
 
-#[derive(Clone)]
-enum I {
-    Y(i32),
-    X(i32),
+//fold along axis=n
+pub type I = (Axis, i32);
+
+fn fold(g: &Grid<Pt2d, bool>, (axis, at): I) -> Grid<Pt2d, bool> {
+    g.fold(axis, at, |a, b| a || b)
 }
 
-fn fold(g: &mut Grid<Pt2d, bool>, instr: I) {
-    let pts: Vec<Pt2d> = g
-        .grid
-        .keys()
-        .filter(|(x, y)| match instr {
-            I::Y(f) => y > &f,
-            I::X(f) => x > &f,
-        })
-        .cloned()
-        .collect();
-
-    let reflected: Vec<Pt2d> = pts
-        .iter()
-        .map(|(x, y)| match instr {
-            I::Y(f) => (*x, f - (y - f)),
-            I::X(f) => (f - (x - f), *y),
-        })
-        .collect();
+fn fold_all(g: &Grid<Pt2d, bool>, instrs: &[I]) -> Grid<Pt2d, bool> {
+    instrs.iter().fold(g.clone(), |g, f| fold(&g, *f))
+}
+
+fn render(g: &Grid<Pt2d, bool>) -> String {
+    g.print_2d(|x| if x { '█' } else { ' ' })
+}
+
+pub struct Day13;
+
+impl Day for Day13 {
+    type Parsed = (Grid<Pt2d, bool>, Vec<I>);
+
+    fn parse(input: String) -> Result<(Grid<Pt2d, bool>, Vec<I>), SolveError> {
+        let re_pt = Pattern::new(r"(\d+),(\d+)", "x,y");
+        let re_in = Pattern::new(r"fold along ([xy])=(\d+)", "fold along x|y=n");
+
+        //parse two instructions; our initial points, and the folds
+        let [sp_pts, sp_ins] = split_sections(&input, "points and folds")?;
+
+        let pts: HashMap<Pt2d, bool> = re_pt
+            .parse_lines(&sp_pts, |f| Ok(((f.get(1)?, f.get(2)?), true)))?
+            .into_iter()
+            .collect();
 
-    g.updates(reflected, |_| true);
+        let g: Grid<Pt2d, bool> = Grid::new(pts, (0, 0), false);
 
-    for p in pts {
-        g.grid.remove(&p);
+        let instrs: Vec<I> = re_in.parse_lines(&sp_ins, |f| match f.str(1) {
+            "x" => Ok((Axis::X, f.get(2)?)),
+            _ => Ok((Axis::Y, f.get(2)?)),
+        })?;
+
+        Ok((g, instrs))
+    }
+
+    //part 1: perform one fold, count dots
+    fn part1((g, instrs): &(Grid<Pt2d, bool>, Vec<I>)) -> Result<impl Display, SolveError> {
+        Ok(fold(g, instrs[0]).grid.len())
     }
-}
 
-pub fn day13(input: String) -> Answer {
-    let mut answer = Answer::default();
-    let re_pt = Regex::new(r"(\d+),(\d+)").unwrap();
-    let re_in = Regex::new(r"fold along (\w)=(\d+)").unwrap();
-
-    //parse two instructions; our initial points, and the folds
-    let sp = input.split("\n\n").collect::<Vec<_>>();
-    let pts: HashMap<Pt2d, bool> = re_pt
-        .captures_iter(sp[0])
-        .map(|c| ((c[1].parse().unwrap(), c[2].parse().unwrap()), true))
-        .collect();
-
-    let mut g: Grid<Pt2d, bool> = Grid::new(pts, (0, 0), false);
-
-    let instrs: Vec<I> = re_in
-        .captures_iter(sp[1])
-        .map(|c| match &c[1] {
-            "x" => I::X(c[2].parse().unwrap()),
-            _ => I::Y(c[2].parse().unwrap()),
+    //part 2: perform all folds and read the resulting message, showing the whole drawing if it can't be read
+    fn part2((g, instrs): &(Grid<Pt2d, bool>, Vec<I>)) -> Result<impl Display, SolveError> {
+        let folded = fold_all(g, instrs);
+        letters::read(&folded).map_err(|e| {
+            SolveError::new(
+                Phase::Part2,
+                format!("{}\nin the message:{}", e, render(&folded)),
+            )
         })
-        .collect();
+    }
 
-    answer.record_parsed();
+    //the message as drawn, to check against the letters read
+    fn extras(
+        (g, instrs): &(Grid<Pt2d, bool>, Vec<I>),
+    ) -> Result<Vec<(String, String)>, SolveError> {
+        Ok(vec![(
+            String::from("Part 2 render"),
+            render(&fold_all(g, instrs)),
+        )])
+    }
+}
 
-    //part 1: perform one fold, count dots
-    fold(&mut g, instrs[0].clone());
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::runner::{assert_example, run};
+    use crate::utils::Parts;
 
-    answer.record(&g.grid.len());
+    const EXAMPLE: &str = "6,10
+0,14
+9,10
+0,3
+10,4
+4,11
+6,0
+6,12
+4,1
+0,13
+10,12
+3,4
+3,0
+8,4
+1,10
+2,14
+8,10
+9,0
 
-    //part 2: perform all folds and retrieve resulting message
+fold along y=7
+fold along x=5";
 
-    for f in instrs[1..].into_iter().cloned() {
-        fold(&mut g, f);
+    #[test]
+    fn example() {
+        assert_example::<Day13>(EXAMPLE, Some("17"), None);
     }
-    answer.record(&g.print_2d(|x| if x { '█' } else { ' ' }));
 
-    answer
+    //the example folds into a square, which isn't a letter, so the error shows it instead
+    #[test]
+    fn example_square() {
+        let parsed = Day13::parse(EXAMPLE.to_string()).unwrap();
+        let square = "\n█████\n█   █\n█   █\n█   █\n█████\n";
+        assert_eq!(render(&fold_all(&parsed.0, &parsed.1)), square);
+
+        let e = run::<Day13>(EXAMPLE.to_string(), Parts::Both)
+            .err()
+            .unwrap();
+        assert_eq!(e.phase, Phase::Part2);
+        assert!(e.msg.starts_with("Unrecognised letter 1 at column 0"));
+        assert!(e.msg.ends_with(&format!("in the message:{}", square)));
+    }
 }
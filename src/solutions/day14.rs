@@ -1,25 +1,28 @@
-use crate::utils::Answer;
+use crate::utils::parse::{split_sections, Pattern};
+use crate::utils::{Day, Phase, SolveError};
 use itertools::Itertools;
-use regex::Regex;
 use std::collections::HashMap;
+use std::fmt::Display;
 
-type TCounts = HashMap<(char, char), usize>;
-type Recipies = HashMap<(char, char), char>;
+pub type TCounts = HashMap<(char, char), usize>;
+pub type Recipies = HashMap<(char, char), char>;
 
-fn to_char<'a>(c: &'a str) -> char {
-    c.chars().nth(0).unwrap()
+fn to_char(c: &str) -> char {
+    c.chars().next().unwrap()
 }
 
-fn step(tuples: TCounts, recipies: &Recipies) -> TCounts {
+fn step(tuples: TCounts, recipies: &Recipies, phase: Phase) -> Result<TCounts, SolveError> {
     let mut next: TCounts = HashMap::new();
 
     for ((a, b), n) in tuples.into_iter() {
-        let c = recipies.get(&(a, b)).unwrap();
+        let c = recipies
+            .get(&(a, b))
+            .ok_or_else(|| SolveError::new(phase, format!("No insertion rule for `{}{}`", a, b)))?;
         *next.entry((a, *c)).or_insert(0) += n;
         *next.entry((*c, b)).or_insert(0) += n;
     }
 
-    next
+    Ok(next)
 }
 
 fn score(tuples: &TCounts, last: &char) -> usize {
@@ -31,37 +34,91 @@ fn score(tuples: &TCounts, last: &char) -> usize {
     c_counts.values().max().unwrap() - c_counts.values().min().unwrap()
 }
 
-pub fn day14(input: String) -> Answer {
-    let mut answer = Answer::default();
-    let re = Regex::new(r"(\w)(\w) -> (\w)").unwrap();
+//score the polymer after some number of steps
+fn grow(
+    (tuples, last, recipies): &Polymer,
+    steps: usize,
+    phase: Phase,
+) -> Result<usize, SolveError> {
+    let mut tuples = tuples.clone();
+    for _ in 0..steps {
+        tuples = step(tuples, recipies, phase)?
+    }
+
+    Ok(score(&tuples, last))
+}
+
+//starting tuple counts, the template's last char, and the insertion rules
+pub type Polymer = (TCounts, char, Recipies);
+
+pub struct Day14;
+
+impl Day for Day14 {
+    type Parsed = Polymer;
 
     //parse into starting tuple gangs and recipies
-    let sp = input.split("\n\n").collect::<Vec<_>>();
-    let mut tuples: TCounts = sp[0].chars().tuple_windows::<(char, char)>().counts();
-    let last = sp[0].chars().last().unwrap(); //get the last for edge cases where the last char is the most/least common
+    fn parse(input: String) -> Result<Polymer, SolveError> {
+        let re = Pattern::new(r"(\w)(\w) -> (\w)", "AB -> C");
+
+        let [template, rules] = split_sections(&input, "a template and rules")?;
+        let template = template.text;
+        let tuples: TCounts = template.chars().tuple_windows::<(char, char)>().counts();
+        let last = template
+            .chars()
+            .last()
+            .ok_or_else(|| SolveError::parse("Empty polymer template").at_line(1))?; //get the last for edge cases where the last char is the most/least common
 
-    let recipies: HashMap<(char, char), char> = re
-        .captures_iter(sp[1])
-        .map(|c| ((to_char(&c[1]), to_char(&c[2])), to_char(&c[3])))
-        .collect();
+        let recipies: Recipies = re
+            .parse_lines(&rules, |f| {
+                Ok(((to_char(f.str(1)), to_char(f.str(2))), to_char(f.str(3))))
+            })?
+            .into_iter()
+            .collect();
+
+        Ok((tuples, last, recipies))
+    }
 
     //since constructing the whole string would take forever, we instead start with
     //a series of tuple counts, and then extrapolate what tuple counts they should create
     //based on our recipies.
 
     //part 1: 10 steps
-    for _ in 0..10 {
-        tuples = step(tuples, &recipies)
+    fn part1(polymer: &Polymer) -> Result<impl Display, SolveError> {
+        grow(polymer, 10, Phase::Part1)
     }
-    answer.record(&(score(&tuples, &last)));
 
-    //part 2: 30 steps more
-    for _ in 0..30 {
-        tuples = step(tuples, &recipies)
+    //part 2: 40 steps
+    fn part2(polymer: &Polymer) -> Result<impl Display, SolveError> {
+        grow(polymer, 40, Phase::Part2)
     }
-    answer.record(&(score(&tuples, &last)));
+}
 
-    answer.record_parsed();
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::runner::assert_example;
 
-    answer
+    const EXAMPLE: &str = "NNCB
+
+CH -> B
+HH -> N
+CB -> H
+NH -> C
+HB -> C
+HC -> B
+HN -> C
+NN -> C
+BH -> H
+NC -> B
+NB -> B
+BN -> B
+BB -> N
+BC -> B
+CC -> N
+CN -> C";
+
+    #[test]
+    fn example() {
+        assert_example::<Day14>(EXAMPLE, Some("1588"), Some("2188189693529"));
+    }
 }
@@ -1,35 +1,18 @@
-use crate::utils::{Answer, Grid, Point, Pt2d};
-use itertools::Itertools;
-use std::collections::{BTreeSet, HashMap, HashSet};
+use crate::utils::parse::{dense_grid, Section};
+use crate::utils::pathfinding::astar;
+use crate::utils::{Day, DenseGrid, Phase, Pt2d, SolveError};
+use std::fmt::Display;
 
-fn in_bounds(b: i32, (x, y): &Pt2d) -> bool {
-    (*x >= 0) && (*x <= b) && (*y >= 0) && (*y <= b)
+//cheapest route from top-left to bottom-right, paying the risk of each position entered
+fn find(g: &DenseGrid<usize>) -> Result<usize, SolveError> {
+    let (_, _, max_x, max_y) = g.bounds();
+    astar(g, (0, 0), (max_x, max_y), |_, to| to, |_, _| true)
+        .map(|p| p.cost)
+        .ok_or_else(|| SolveError::new(Phase::Part1, "No route to the bottom-right"))
 }
 
-fn find(g: &Grid<Pt2d, usize>, max: i32) -> usize {
-    let mut distances: BTreeSet<(usize, Pt2d)> = BTreeSet::from([(0, (0, 0))]);
-    let mut visited: HashSet<Pt2d> = HashSet::new();
-
-    while distances.first().unwrap().1 != (max, max) {
-        //visit closest
-        let (v, c) = distances.pop_first().unwrap();
-        visited.insert(c);
-
-        //find distance to neighbours
-        let ns = c
-            .neighbours_card()
-            .into_iter()
-            .filter(|n| in_bounds(max, n) && !visited.contains(n))
-            .map(|n| (v + g.get_def(&n), n));
-
-        //append neighbours to distances btreeset
-        distances.extend(ns);
-    }
-    distances.pop_first().unwrap().0
-}
-
-fn meta_val(v: usize, x: usize, y: usize) -> usize {
-    let new_v = v + x + y;
+fn meta_val(v: usize, (x, y): Pt2d) -> usize {
+    let new_v = v + (x + y) as usize;
 
     if new_v > 9 {
         new_v % 9
@@ -38,40 +21,48 @@ fn meta_val(v: usize, x: usize, y: usize) -> usize {
     }
 }
 
-pub fn day15(input: String) -> Answer {
-    let mut answer = Answer::default();
+pub struct Day15;
 
-    //parse
-    let size = f64::sqrt(input.len() as f64) as i32;
-    let g = Grid::from(input, 10, |c| c.to_digit(10).unwrap() as usize);
+impl Day for Day15 {
+    type Parsed = DenseGrid<usize>;
 
-    answer.record_parsed();
+    //parse
+    fn parse(input: String) -> Result<DenseGrid<usize>, SolveError> {
+        dense_grid(&Section::whole(&input), 10, |c| {
+            c.to_digit(10).map(|d| d as usize)
+        })
+        .map(|(g, _)| g)
+    }
 
     //part 1: find cheapest route to bottom-right
-    let p1 = find(&g, size - 1);
-    answer.record(&p1);
+    fn part1(g: &DenseGrid<usize>) -> Result<impl Display, SolveError> {
+        find(g)
+    }
 
     //part 2: inflate grid and find cheapest route again
-    let m: HashMap<Pt2d, usize> = g
-        .grid
-        .iter()
-        .map(|((x, y), v)| {
-            (0..5)
-                .cartesian_product(0..5)
-                .map(|(meta_x, meta_y)| {
-                    (
-                        (x + (size * meta_x), y + (size * meta_y)),
-                        meta_val(*v, meta_x as usize, meta_y as usize),
-                    )
-                })
-                .collect::<Vec<_>>()
-        })
-        .flatten()
-        .collect();
-    let g2 = Grid::new(m, (0, 0), 10);
+    fn part2(g: &DenseGrid<usize>) -> Result<impl Display, SolveError> {
+        find(&g.tile(5, 5, meta_val))
+    }
+}
 
-    let p2 = find(&g2, (size * 5) - 1);
-    answer.record(&p2);
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::runner::assert_example;
 
-    answer
+    const EXAMPLE: &str = "1163751742
+1381373672
+2136511328
+3694931569
+7463417111
+1319128137
+1359912421
+3125421639
+1293138521
+2311944581";
+
+    #[test]
+    fn example() {
+        assert_example::<Day15>(EXAMPLE, Some("40"), Some("315"));
+    }
 }
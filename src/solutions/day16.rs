@@ -1,32 +1,33 @@
-use crate::utils::{bit_to_n, Answer};
+use crate::utils::{bit_to_n, Day, SolveError};
+use std::fmt::Display;
 
 #[derive(Debug, Clone)]
-enum P {
+pub enum P {
     P(Vec<Packet>),
     L(usize),
 }
 
 #[derive(Debug, Clone)]
-struct Packet {
-    version: usize,
-    p_type: usize,
-    payload: P,
+pub struct Packet {
+    pub version: usize,
+    pub p_type: usize,
+    pub payload: P,
 }
 
-fn version_sum(p: &Packet) -> usize {
+pub fn version_sum(p: &Packet) -> usize {
     p.version
         + match &p.payload {
             P::L(_) => 0,
-            P::P(subs) => subs.iter().map(|s| version_sum(s)).sum(),
+            P::P(subs) => subs.iter().map(version_sum).sum(),
         }
 }
 
-fn execute_packet(p: Packet) -> usize {
+pub fn execute_packet(p: Packet) -> usize {
     match p.payload {
         P::L(x) => x,
         P::P(xs) => {
             if p.p_type < 4 {
-                let vs = xs.into_iter().map(|x| execute_packet(x));
+                let vs = xs.into_iter().map(execute_packet);
                 match p.p_type {
                     0 => vs.sum(),
                     1 => vs.product(),
@@ -51,17 +52,24 @@ fn execute_packet(p: Packet) -> usize {
     }
 }
 
-fn construct_packet(queue: Vec<usize>) -> (Packet, Vec<usize>) {
-    let ver = bit_to_n(&queue[0..3]);
-    let p_t = bit_to_n(&queue[3..6]);
+//get a run of bits, failing if the transmission ends first
+fn bits(queue: &[usize], from: usize, to: usize) -> Result<&[usize], SolveError> {
+    queue
+        .get(from..to)
+        .ok_or_else(|| SolveError::parse("Transmission ended partway through a packet"))
+}
+
+pub fn construct_packet(queue: Vec<usize>) -> Result<(Packet, Vec<usize>), SolveError> {
+    let ver = bit_to_n(bits(&queue, 0, 3)?);
+    let p_t = bit_to_n(bits(&queue, 3, 6)?);
     let mut remain;
 
-    let pl = match (p_t, queue[6]) {
+    let pl = match (p_t, bits(&queue, 6, 7)?[0]) {
         (4, _) => {
             let mut i = 6;
             let mut res = vec![];
             loop {
-                res.extend(queue[i + 1..i + 5].to_vec());
+                res.extend(bits(&queue, i + 1, i + 5)?);
                 if queue[i] == 1 {
                     i += 5;
                 } else {
@@ -72,23 +80,23 @@ fn construct_packet(queue: Vec<usize>) -> (Packet, Vec<usize>) {
             P::L(bit_to_n(&res))
         }
         (_, 0) => {
-            let pl_sz = bit_to_n(&queue[7..22]) as usize;
+            let pl_sz = bit_to_n(bits(&queue, 7, 22)?);
+            let mut sub = bits(&queue, 22, 22 + pl_sz)?.to_vec();
             remain = queue[22 + pl_sz..].to_vec();
-            let mut sub = queue[22..22 + pl_sz].to_vec();
             let mut pls = vec![];
-            while sub.len() > 0 {
-                let (new_pl, new_sub) = construct_packet(sub);
+            while !sub.is_empty() {
+                let (new_pl, new_sub) = construct_packet(sub)?;
                 pls.push(new_pl);
                 sub = new_sub;
             }
             P::P(pls)
         }
         _ => {
-            let sub_num = bit_to_n(&queue[7..18]);
+            let sub_num = bit_to_n(bits(&queue, 7, 18)?);
             let mut pls = vec![];
             remain = queue[18..].to_vec();
             for _ in 0..sub_num {
-                let (new_pl, new_sub) = construct_packet(remain);
+                let (new_pl, new_sub) = construct_packet(remain)?;
                 pls.push(new_pl);
                 remain = new_sub;
             }
@@ -96,42 +104,112 @@ fn construct_packet(queue: Vec<usize>) -> (Packet, Vec<usize>) {
         }
     };
 
+    //operators need something to operate on, and comparisons exactly two things to compare
+    match (&pl, p_t) {
+        (P::P(xs), 0..=3) if xs.is_empty() => {
+            return Err(SolveError::parse(format!(
+                "Operator packet of type {} is empty",
+                p_t
+            )))
+        }
+        (P::P(xs), 5..=7) if xs.len() != 2 => {
+            return Err(SolveError::parse(format!(
+                "Comparison packet of type {} has {} sub-packets",
+                p_t,
+                xs.len()
+            )))
+        }
+        _ => (),
+    }
+
     let p = Packet {
         version: ver,
         p_type: p_t,
         payload: pl,
     };
 
-    (p, remain)
+    Ok((p, remain))
+}
+
+//hex to bit vec
+pub fn hex_to_bits(input: &str) -> Result<Vec<usize>, SolveError> {
+    let mut res = vec![];
+
+    for (i, c) in input.chars().enumerate() {
+        let d = c
+            .to_digit(16)
+            .ok_or_else(|| SolveError::parse(format!("{:?} is not a hex digit", c)).at(1, i + 1))?;
+        res.extend((0..4).rev().map(|b| ((d >> b) & 1) as usize));
+    }
+
+    Ok(res)
+}
+
+//decode a hex transmission into its outermost packet, ignoring any trailing newline
+pub fn decode(input: &str) -> Result<Packet, SolveError> {
+    Ok(construct_packet(hex_to_bits(input.trim_end())?)?.0)
 }
 
-pub fn day16(input: String) -> Answer {
-    let mut answer = Answer::default();
-
-    //parse hex to bit vec
-    let b_vec: Vec<usize> = input
-        .chars()
-        .map(|c| {
-            let x = format!("0000{:b}", c.to_digit(16).unwrap());
-            x.chars()
-                .map(|cc| cc.to_digit(10).unwrap() as usize)
-                .collect::<Vec<usize>>()[x.len() - 4..]
-                .to_vec()
-        })
-        .flatten()
-        .collect();
+pub struct Day16;
+
+impl Day for Day16 {
+    type Parsed = Packet;
 
     //parse into packets
-    let (ps, _) = construct_packet(b_vec);
-    answer.record_parsed();
+    fn parse(input: String) -> Result<Packet, SolveError> {
+        decode(&input)
+    }
 
     //part 1: sum version numbers
-    let p1 = version_sum(&ps);
-    answer.record(&p1);
+    fn part1(ps: &Packet) -> Result<impl Display, SolveError> {
+        Ok(version_sum(ps))
+    }
 
     //part 2: execute
-    let p2 = execute_packet(ps);
-    answer.record(&p2);
+    fn part2(ps: &Packet) -> Result<impl Display, SolveError> {
+        Ok(execute_packet(ps.clone()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::runner::assert_example;
 
-    answer
+    #[test]
+    fn version_sums() {
+        for (hex, sum) in [
+            ("D2FE28", "6"),
+            ("38006F45291200", "9"),
+            ("EE00D40C823060", "14"),
+            ("8A004A801A8002F478", "16"),
+            ("620080001611562C8802118E34", "12"),
+            ("C0015000016115A2E0802F182340", "23"),
+            ("A0016C880162017C3686B18A3D4780", "31"),
+        ] {
+            assert_example::<Day16>(hex, Some(sum), None);
+        }
+    }
+
+    #[test]
+    fn expressions() {
+        for (hex, value) in [
+            ("C200B40A82", "3"),
+            ("04005AC33890", "54"),
+            ("880086C3E88112", "7"),
+            ("CE00C43D881120", "9"),
+            ("D8005AC2A8F0", "1"),
+            ("F600BC2D8F", "0"),
+            ("9C005AC2F8F0", "0"),
+            ("9C0141080250320F1802104A08", "1"),
+        ] {
+            assert_example::<Day16>(hex, None, Some(value));
+        }
+    }
+
+    #[test]
+    fn literal() {
+        assert_eq!(decode("D2FE28").unwrap().version, 6);
+        assert_eq!(execute_packet(decode("D2FE28\n").unwrap()), 2021);
+    }
 }
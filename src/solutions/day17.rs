@@ -1,6 +1,7 @@
-use crate::utils::{Answer, Point, Pt2d};
-use regex::Regex;
+use crate::utils::parse::Pattern;
+use crate::utils::{Day, Point, Pt2d, SolveError};
 use std::cmp::Ordering;
+use std::fmt::Display;
 
 //accelerate via gravity and drag
 fn accel((x, y): Pt2d) -> Pt2d {
@@ -15,7 +16,7 @@ fn accel((x, y): Pt2d) -> Pt2d {
 }
 
 //get the max height if there is eventually an intersection with the target bounds
-fn height_from(bounds: &(i32, i32, i32, i32), mut vel: Pt2d) -> i32 {
+fn height_from(bounds: &Bounds, mut vel: Pt2d) -> i32 {
     let mut res = 0;
     let mut collided = false;
     let mut ptr = (0, 0);
@@ -36,33 +37,60 @@ fn height_from(bounds: &(i32, i32, i32, i32), mut vel: Pt2d) -> i32 {
         -1
     }
 }
-pub fn day17(input: String) -> Answer {
-    let mut answer = Answer::default();
-    let re = Regex::new(r"target area: x=(.+)\.\.(.+), y=(.+)\.\.(.+)").unwrap();
 
-    //parse target area
-    let c = re.captures(&input).unwrap();
-    let bounds = (
-        c[1].parse::<i32>().unwrap(),
-        c[2].parse::<i32>().unwrap(),
-        c[3].parse::<i32>().unwrap(),
-        c[4].parse::<i32>().unwrap(),
-    );
-    answer.record_parsed();
-
-    let mut max_h = 0;
-    let mut on_target = 0;
+//max heights of every launch velocity that hits the target
+fn on_target(bounds: &Bounds) -> Vec<i32> {
+    let mut heights = vec![];
     for x in 1..bounds.1 + 1 {
         for y in (-(1 - bounds.2)..1 - bounds.2).rev() {
-            match height_from(&bounds, (x, y)) {
+            match height_from(bounds, (x, y)) {
                 -1 => {}
-                v => {
-                    max_h = i32::max(max_h, v);
-                    on_target += 1;
-                }
+                v => heights.push(v),
             }
         }
     }
-    answer.record_both(&max_h, &on_target);
-    answer
+    heights
+}
+
+//target area as (min x, max x, min y, max y)
+pub type Bounds = (i32, i32, i32, i32);
+
+pub struct Day17;
+
+impl Day for Day17 {
+    type Parsed = Bounds;
+
+    //parse target area
+    fn parse(input: String) -> Result<Bounds, SolveError> {
+        let re = Pattern::new(
+            r"target area: x=(.+)\.\.(.+), y=(.+)\.\.(.+)",
+            "target area: x=a..b, y=c..d",
+        );
+
+        let f = re.fields(&input, 0)?;
+        Ok((f.get(1)?, f.get(2)?, f.get(3)?, f.get(4)?))
+    }
+
+    //part 1: highest point of any trajectory that hits the target
+    fn part1(bounds: &Bounds) -> Result<impl Display, SolveError> {
+        Ok(on_target(bounds).into_iter().fold(0, i32::max))
+    }
+
+    //part 2: number of launch velocities that hit the target
+    fn part2(bounds: &Bounds) -> Result<impl Display, SolveError> {
+        Ok(on_target(bounds).len())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::runner::assert_example;
+
+    const EXAMPLE: &str = "target area: x=20..30, y=-10..-5";
+
+    #[test]
+    fn example() {
+        assert_example::<Day17>(EXAMPLE, Some("45"), Some("112"));
+    }
 }
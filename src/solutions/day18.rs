@@ -1,36 +1,40 @@
 use crate::eval::{eval, Value};
-use crate::utils::Answer;
+use crate::utils::{Day, SolveError};
 use itertools::Itertools;
 use regex::Regex;
+use std::fmt::Display;
 
 #[derive(Debug, Clone)]
-enum Shnumber {
+pub enum Shnumber {
     Single(u32),
     Dub(Box<Shnumber>, Box<Shnumber>),
 }
 
 //parsing
-fn to_shnumber(arr: Value) -> Shnumber {
+fn to_shnumber(arr: Value) -> Option<Shnumber> {
     match arr {
-        Value::Array(xs) => Shnumber::Dub(
-            Box::new(to_shnumber(xs[0].clone())),
-            Box::new(to_shnumber(xs[1].clone())),
-        ),
-        Value::Number(x) => Shnumber::Single(x.as_u64().unwrap() as u32),
-        _ => panic!("Invalid input!"),
+        Value::Array(xs) if xs.len() == 2 => Some(Shnumber::Dub(
+            Box::new(to_shnumber(xs[0].clone())?),
+            Box::new(to_shnumber(xs[1].clone())?),
+        )),
+        Value::Number(x) => Some(Shnumber::Single(x.as_u64()? as u32)),
+        _ => None,
     }
 }
 
-fn shparse(input: &str) -> Shnumber {
+fn shparse(input: &str, line: usize) -> Result<Shnumber, SolveError> {
+    let invalid =
+        || SolveError::parse(format!("Invalid snailfish number `{}`", input)).at_line(line + 1);
+
     let op = Regex::new(r"\[").unwrap();
     let cl = Regex::new(r"\]").unwrap();
 
     let x = op.replace_all(input, "array(");
     let y = cl.replace_all(&x, ")");
 
-    let res = eval(&y).unwrap();
+    let res = eval(&y).map_err(|_| invalid())?;
 
-    to_shnumber(res)
+    to_shnumber(res).ok_or_else(invalid)
 }
 
 //reduction
@@ -60,26 +64,26 @@ fn a_r(a: Shnumber, b: Option<Shnumber>) -> Shnumber {
 
 fn explode(s: Shnumber, n: u32) -> (bool, Option<Shnumber>, Shnumber, Option<Shnumber>) {
     match (s, n) {
-        (v @ Shnumber::Single(_), _) => return (false, None, v, None),
-        (Shnumber::Dub(v1, v2), 0) => return (true, Some(*v1), Shnumber::Single(0), Some(*v2)),
+        (v @ Shnumber::Single(_), _) => (false, None, v, None),
+        (Shnumber::Dub(v1, v2), 0) => (true, Some(*v1), Shnumber::Single(0), Some(*v2)),
         (Shnumber::Dub(v1, v2), m) => {
             let (c1, l, v3, r) = explode(*v1.clone(), m - 1);
             if c1 {
-                return (
+                (
                     true,
                     l,
                     Shnumber::Dub(Box::new(v3), Box::new(a_l(*v2, r))),
                     None,
-                );
+                )
             } else {
                 let (c2, l, v4, r) = explode(*v2.clone(), m - 1);
                 if c2 {
-                    return (
+                    (
                         true,
                         None,
                         Shnumber::Dub(Box::new(a_r(v3, l)), Box::new(v4)),
                         r,
-                    );
+                    )
                 } else {
                     (false, None, Shnumber::Dub(v1, v2), None)
                 }
@@ -92,7 +96,7 @@ fn split(s: Shnumber) -> (bool, Shnumber) {
     match s {
         Shnumber::Single(v) => {
             if v >= 10 {
-                (true, to_dub(v / 2, (v + 1) / 2))
+                (true, to_dub(v / 2, v.div_ceil(2)))
             } else {
                 (false, Shnumber::Single(v))
             }
@@ -136,37 +140,141 @@ fn shmagnitude(s: Shnumber) -> u32 {
     }
 }
 
-pub fn day18(input: String) -> Answer {
-    let mut answer = Answer::default();
+pub struct Day18;
+
+impl Day for Day18 {
+    type Parsed = Vec<Shnumber>;
 
     //parse shnumbers
-    let shnumbs: Vec<Shnumber> = input.lines().map(|l| shparse(l)).collect();
-    answer.record_parsed();
+    fn parse(input: String) -> Result<Vec<Shnumber>, SolveError> {
+        let shnumbs: Vec<Shnumber> = input
+            .lines()
+            .enumerate()
+            .map(|(i, l)| shparse(l, i))
+            .collect::<Result<_, _>>()?;
+
+        if shnumbs.is_empty() {
+            return Err(SolveError::parse("Expected at least one snailfish number"));
+        }
+
+        Ok(shnumbs)
+    }
 
     //part 1: add-reduce all numbers
-    let p1 = shnumbs
-        .clone()
-        .into_iter()
-        .reduce(|a, b| shadd(a, b))
-        .unwrap();
+    fn part1(shnumbs: &Vec<Shnumber>) -> Result<impl Display, SolveError> {
+        let p1 = shnumbs.clone().into_iter().reduce(shadd).unwrap();
 
-    answer.record(&shmagnitude(p1));
+        Ok(shmagnitude(p1))
+    }
 
     //part 2: find highest magnitude of two additions
-    let l = shnumbs.len();
-    let mut max = 0;
+    fn part2(shnumbs: &Vec<Shnumber>) -> Result<impl Display, SolveError> {
+        let l = shnumbs.len();
+        let mut max = 0;
 
-    for (x, y) in (0..l).cartesian_product(0..l) {
-        if x == y {
-            continue;
+        for (x, y) in (0..l).cartesian_product(0..l) {
+            if x == y {
+                continue;
+            }
+            max = u32::max(
+                max,
+                shmagnitude(shadd(shnumbs[x].clone(), shnumbs[y].clone())),
+            )
+        }
+
+        Ok(max)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::runner::assert_example;
+
+    const HOMEWORK: &str = "[[[0,[5,8]],[[1,7],[9,6]]],[[4,[1,2]],[[1,4],2]]]
+[[[5,[2,8]],4],[5,[[9,9],0]]]
+[6,[[[6,2],[5,6]],[[7,6],[4,7]]]]
+[[[6,[0,7]],[0,9]],[4,[9,[9,0]]]]
+[[[7,[6,4]],[3,[1,3]]],[[[5,5],1],9]]
+[[6,[[7,3],[3,2]]],[[[3,8],[5,7]],4]]
+[[[[5,4],[7,7]],8],[[8,3],8]]
+[[9,3],[[9,9],[6,[4,9]]]]
+[[2,[[7,7],7]],[[5,8],[[9,3],[0,2]]]]
+[[[[5,2],5],[8,[3,7]]],[[5,[7,5]],[4,4]]]";
+
+    fn sh(s: &str) -> Shnumber {
+        shparse(s, 0).unwrap()
+    }
+
+    fn show(s: &Shnumber) -> String {
+        match s {
+            Shnumber::Single(v) => v.to_string(),
+            Shnumber::Dub(a, b) => format!("[{},{}]", show(a), show(b)),
+        }
+    }
+
+    fn sum(ss: &[&str]) -> String {
+        show(&ss.iter().map(|s| sh(s)).reduce(shadd).unwrap())
+    }
+
+    #[test]
+    fn explodes() {
+        for (before, after) in [
+            ("[[[[[9,8],1],2],3],4]", "[[[[0,9],2],3],4]"),
+            ("[7,[6,[5,[4,[3,2]]]]]", "[7,[6,[5,[7,0]]]]"),
+            ("[[6,[5,[4,[3,2]]]],1]", "[[6,[5,[7,0]]],3]"),
+            (
+                "[[3,[2,[1,[7,3]]]],[6,[5,[4,[3,2]]]]]",
+                "[[3,[2,[8,0]]],[9,[5,[4,[3,2]]]]]",
+            ),
+            (
+                "[[3,[2,[8,0]]],[9,[5,[4,[3,2]]]]]",
+                "[[3,[2,[8,0]]],[9,[5,[7,0]]]]",
+            ),
+        ] {
+            assert_eq!(show(&explode(sh(before), 4).2), after);
         }
-        max = u32::max(
-            max,
-            shmagnitude(shadd(shnumbs[x].clone(), shnumbs[y].clone())),
-        )
     }
 
-    answer.record(&max);
+    #[test]
+    fn reductions() {
+        assert_eq!(
+            sum(&["[[[[4,3],4],4],[7,[[8,4],9]]]", "[1,1]"]),
+            "[[[[0,7],4],[[7,8],[6,0]]],[8,1]]"
+        );
+        assert_eq!(
+            sum(&["[1,1]", "[2,2]", "[3,3]", "[4,4]"]),
+            "[[[[1,1],[2,2]],[3,3]],[4,4]]"
+        );
+        assert_eq!(
+            sum(&["[1,1]", "[2,2]", "[3,3]", "[4,4]", "[5,5]"]),
+            "[[[[3,0],[5,3]],[4,4]],[5,5]]"
+        );
+        assert_eq!(
+            sum(&["[1,1]", "[2,2]", "[3,3]", "[4,4]", "[5,5]", "[6,6]"]),
+            "[[[[5,0],[7,4]],[5,5]],[6,6]]"
+        );
+    }
 
-    answer
+    #[test]
+    fn magnitudes() {
+        for (s, m) in [
+            ("[[1,2],[[3,4],5]]", 143),
+            ("[[[[0,7],4],[[7,8],[6,0]]],[8,1]]", 1384),
+            ("[[[[1,1],[2,2]],[3,3]],[4,4]]", 445),
+            ("[[[[3,0],[5,3]],[4,4]],[5,5]]", 791),
+            ("[[[[5,0],[7,4]],[5,5]],[6,6]]", 1137),
+            (
+                "[[[[8,7],[7,7]],[[8,6],[7,7]]],[[[0,7],[6,6]],[8,7]]]",
+                3488,
+            ),
+        ] {
+            assert_eq!(shmagnitude(sh(s)), m);
+        }
+    }
+
+    #[test]
+    fn homework() {
+        assert_example::<Day18>(HOMEWORK, Some("4140"), Some("3993"));
+    }
 }
@@ -1,7 +1,9 @@
-use crate::utils::{Answer, Point, Pt3d};
+use crate::utils::parse::{list, sections};
+use crate::utils::{checkpoint, Day, Phase, Point, Pt3d, SolveError};
 use itertools::Itertools;
-use regex::Regex;
+use std::cell::OnceCell;
 use std::collections::HashSet;
+use std::fmt::Display;
 
 fn ind(p: &Pt3d, n: i32) -> i32 {
     match n {
@@ -39,35 +41,25 @@ fn axis_mutations(pts: &HashSet<Pt3d>) -> Vec<HashSet<Pt3d>> {
         .collect()
 }
 
-pub fn day19(input: String) -> Answer {
-    let mut answer = Answer::default();
-    let re = Regex::new(r"(.+),(.+),(.+)").unwrap();
+//every beacon relative to the first scanner, and the position of each other scanner
+pub type Ocean = (HashSet<Pt3d>, Vec<Pt3d>);
 
-    //parse into hashmaps
-    let mut signals: Vec<HashSet<Pt3d>> = input
-        .split("\n\n")
-        .map(|s| {
-            re.captures_iter(s)
-                .map(|c| {
-                    (
-                        c[1].parse().unwrap(),
-                        c[2].parse().unwrap(),
-                        c[3].parse().unwrap(),
-                    )
-                })
-                .collect()
-        })
-        .collect();
+//the beacons each scanner sees, and them all aligned once either part has needed them
+pub struct Scanners {
+    pub signals: Vec<HashSet<Pt3d>>,
+    aligned: OnceCell<Ocean>,
+}
+
+//'attach' all pieces together, each scanner turned and moved to match one already placed
+fn align(signals: &[HashSet<Pt3d>]) -> Result<Ocean, SolveError> {
+    let mut signals = signals.to_vec();
 
     //absolutes is all signals from 0th probe
     let mut absolutes: HashSet<Pt3d> = signals.remove(0);
 
-    answer.record_parsed();
-
-    //part 1: 'attach' all pieces together.
     let mut ts = vec![];
-    while signals.len() > 0 {
-        println!("Segments remaining: {}", signals.len());
+    while !signals.is_empty() {
+        let remaining = signals.len();
         'outermost: for i in 0..signals.len() {
             for s in axis_mutations(&signals[i]) {
                 for p1 in absolutes.iter() {
@@ -85,17 +77,251 @@ pub fn day19(input: String) -> Answer {
                 }
             }
         }
+
+        if signals.len() == remaining {
+            return Err(SolveError::new(
+                Phase::Part1,
+                format!("Could not align the remaining {} scanners", remaining),
+            ));
+        }
     }
-    answer.record(&absolutes.len());
 
-    //part 2: find the greatest manhattan distance between two points
-    let mut man = 0;
-    for ((x1, y1, z1), (x2, y2, z2)) in ts.iter().tuple_combinations() {
-        let m = i32::abs(x2 - x1) + i32::abs(y2 - y1) + i32::abs(z2 - z1);
-        man = i32::max(man, m);
+    Ok((absolutes, ts))
+}
+
+//the aligned scanners, aligning them for whichever part comes first
+fn aligned(s: &Scanners) -> Result<&Ocean, SolveError> {
+    if let Some(o) = s.aligned.get() {
+        return Ok(o);
     }
+    let o = align(&s.signals)?;
+    checkpoint("align scanners");
+    Ok(s.aligned.get_or_init(|| o))
+}
+
+pub struct Day19;
+
+impl Day for Day19 {
+    type Parsed = Scanners;
+
+    //parse each scanner's beacons
+    fn parse(input: String) -> Result<Scanners, SolveError> {
+        let mut signals: Vec<HashSet<Pt3d>> = vec![];
 
-    answer.record(&man);
+        //each scanner is a `--- scanner n ---` header and a beacon per line
+        for s in sections(&input) {
+            let mut beacons = HashSet::new();
+            for (i, l) in s.lines().filter(|(_, l)| !l.starts_with("---")) {
+                match list::<i32>(l, ",", i)?[..] {
+                    [x, y, z] => beacons.insert((x, y, z)),
+                    _ => {
+                        return Err(
+                            SolveError::parse(format!("Expected `x,y,z`, found `{}`", l))
+                                .at_line(i + 1),
+                        )
+                    }
+                };
+            }
+            signals.push(beacons);
+        }
+
+        if signals.is_empty() {
+            return Err(SolveError::parse("Expected at least one scanner"));
+        }
 
-    answer
+        Ok(Scanners {
+            signals,
+            aligned: OnceCell::new(),
+        })
+    }
+
+    //part 1: align the scanners and count the beacons
+    fn part1(s: &Scanners) -> Result<impl Display, SolveError> {
+        let (absolutes, _) = aligned(s)?;
+        Ok(absolutes.len())
+    }
+
+    //part 2: find the greatest manhattan distance between two scanners
+    fn part2(s: &Scanners) -> Result<impl Display, SolveError> {
+        let (_, ts) = aligned(s)?;
+        let mut man = 0;
+        for ((x1, y1, z1), (x2, y2, z2)) in ts.iter().tuple_combinations() {
+            let m = i32::abs(x2 - x1) + i32::abs(y2 - y1) + i32::abs(z2 - z1);
+            man = i32::max(man, m);
+        }
+        checkpoint("max distance");
+
+        Ok(man)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::runner::{assert_example, run};
+    use crate::utils::Parts;
+
+    const EXAMPLE: &str = "--- scanner 0 ---
+404,-588,-901
+528,-643,409
+-838,591,734
+390,-675,-793
+-537,-823,-458
+-485,-357,347
+-345,-311,381
+-661,-816,-575
+-876,649,763
+-618,-824,-621
+553,345,-567
+474,580,667
+-447,-329,318
+-584,868,-557
+544,-627,-890
+564,392,-477
+455,729,728
+-892,524,684
+-689,845,-530
+423,-701,434
+7,-33,-71
+630,319,-379
+443,580,662
+-789,900,-551
+459,-707,401
+
+--- scanner 1 ---
+686,422,578
+605,423,415
+515,917,-361
+-336,658,858
+95,138,22
+-476,619,847
+-340,-569,-846
+567,-361,727
+-460,603,-452
+669,-402,600
+729,430,532
+-500,-761,534
+-322,571,750
+-466,-666,-811
+-429,-592,574
+-355,545,-477
+703,-491,-529
+-328,-685,520
+413,935,-424
+-391,539,-444
+586,-435,557
+-364,-763,-893
+807,-499,-711
+755,-354,-619
+553,889,-390
+
+--- scanner 2 ---
+649,640,665
+682,-795,504
+-784,533,-524
+-644,584,-595
+-588,-843,648
+-30,6,44
+-674,560,763
+500,723,-460
+609,671,-379
+-555,-800,653
+-675,-892,-343
+697,-426,-610
+578,704,681
+493,664,-388
+-671,-858,530
+-667,343,800
+571,-461,-707
+-138,-166,112
+-889,563,-600
+646,-828,498
+640,759,510
+-630,509,768
+-681,-892,-333
+673,-379,-804
+-742,-814,-386
+577,-820,562
+
+--- scanner 3 ---
+-589,542,597
+605,-692,669
+-500,565,-823
+-660,373,557
+-458,-679,-417
+-488,449,543
+-626,468,-788
+338,-750,-386
+528,-832,-391
+562,-778,733
+-938,-730,414
+543,643,-506
+-524,371,-870
+407,773,750
+-104,29,83
+378,-903,-323
+-778,-728,485
+426,699,580
+-438,-605,-362
+-469,-447,-387
+509,732,623
+647,635,-688
+-868,-804,481
+614,-800,639
+595,780,-596
+
+--- scanner 4 ---
+727,592,562
+-293,-554,779
+441,611,-461
+-714,465,-776
+-743,427,-804
+-660,-479,-426
+832,-632,460
+927,-485,-438
+408,393,-506
+466,436,-512
+110,16,151
+-258,-428,682
+-393,719,612
+-211,-452,876
+808,-476,-593
+-575,615,604
+-485,667,467
+-680,325,-822
+-627,-443,-432
+872,-547,-609
+833,512,582
+807,604,487
+839,-516,451
+891,-625,532
+-652,-548,-490
+30,-46,-14";
+
+    #[test]
+    fn example() {
+        assert_example::<Day19>(EXAMPLE, Some("79"), Some("3621"));
+    }
+
+    //part 1 times the alignment it caches, and part 2 just the distance search
+    #[test]
+    fn checkpoints() {
+        let a = run::<Day19>(EXAMPLE.to_string(), Parts::Both).unwrap_or_else(|e| panic!("{}", e));
+        let cps: Vec<(Phase, &str)> = a
+            .timings()
+            .iter()
+            .flat_map(|t| {
+                t.checkpoints
+                    .iter()
+                    .map(move |(n, _)| (t.phase, n.as_str()))
+            })
+            .collect();
+        assert_eq!(
+            cps,
+            vec![
+                (Phase::Part1, "align scanners"),
+                (Phase::Part2, "max distance")
+            ]
+        );
+    }
 }
@@ -1,62 +1,73 @@
-use itertools::Itertools;
-
-use crate::utils::{bit_to_n, Answer, Grid, Point, Pt2d};
-
-type State = Grid<Pt2d, bool>;
-
-fn enhance(enh: &Vec<bool>, state: &State, p: Pt2d) -> (Pt2d, bool) {
-    let pattern: Vec<usize> = p
-        .neighbourhood()
-        .iter()
-        .map(|pn| if state.get_def(pn) { 1 } else { 0 })
-        .collect();
-
-    (p, enh[bit_to_n(&pattern)])
-}
-
-fn enhance_all(enh: &Vec<bool>, state: &mut State) {
-    let (x_min, y_min, x_max, y_max) = state.bounds();
-
-    //update the image and its immediate border
-    let updates: Vec<_> = (x_min - 1..x_max + 2)
-        .cartesian_product(y_min - 1..y_max + 2)
-        .map(|p| enhance(enh, &state, p))
-        .collect();
-
-    state.inserts(updates);
-
-    //update the world state, finding what everything else in the world changes to
-    let blanks: Vec<usize> = [state.default; 9].iter().map(|b| *b as usize).collect();
-    state.default = enh[bit_to_n(&blanks)];
+use crate::utils::automaton::CellularAutomaton;
+use crate::utils::parse::{dense_grid, split_sections};
+use crate::utils::{Day, DenseGrid, Point, SolveError};
+use std::fmt::Display;
+
+pub type State = DenseGrid<bool>;
+
+//count lit pixels after some number of passes, each pixel becoming the entry its 3x3 neighbourhood reads as in binary.
+//the rest of the infinite image may flip too
+fn enhanced((enh, state): &(Vec<bool>, State), passes: usize) -> usize {
+    let mut image = CellularAutomaton::infinite(state.clone(), Point::neighbourhood);
+    for _ in 0..passes {
+        image.step(|_, ns| enh[ns.iter().fold(0, |n, b| n * 2 + *b as usize)]);
+    }
+    image.grid.values().filter(|v| **v).count()
 }
 
-fn count_alight(state: &State) -> usize {
-    state.grid.values().filter(|v| **v).count()
-}
+pub struct Day20;
 
-pub fn day20(input: String) -> Answer {
-    let mut answer = Answer::default();
+impl Day for Day20 {
+    type Parsed = (Vec<bool>, State);
 
     //parse into enhancement seq and starting image
-    let ins: Vec<&str> = input.split("\n\n").collect();
-
-    let enh: Vec<bool> = ins[0].chars().map(|c| c == '#').collect();
-
-    let mut state = Grid::from(ins[1].to_string(), false, |c| c == '#');
-
-    answer.record_parsed();
+    fn parse(input: String) -> Result<(Vec<bool>, State), SolveError> {
+        let [ins_enh, ins_img] = split_sections(&input, "an enhancement sequence and image")?;
+
+        let enh: Vec<bool> = ins_enh.text.chars().map(|c| c == '#').collect();
+        if enh.len() != 512 {
+            return Err(SolveError::parse(format!(
+                "Enhancement sequence has {} characters, expected 512",
+                enh.len()
+            ))
+            .at_line(1));
+        }
+
+        let (state, _) = dense_grid(&ins_img, false, |c| match c {
+            '#' => Some(true),
+            '.' => Some(false),
+            _ => None,
+        })?;
+
+        Ok((enh, state))
+    }
 
     //perform two passes
-    for _ in 0..2 {
-        enhance_all(&enh, &mut state);
+    fn part1(parsed: &(Vec<bool>, State)) -> Result<impl Display, SolveError> {
+        Ok(enhanced(parsed, 2))
     }
-    answer.record(&count_alight(&state));
 
     //perform MOAR passes
-    for _ in 0..48 {
-        enhance_all(&enh, &mut state);
+    fn part2(parsed: &(Vec<bool>, State)) -> Result<impl Display, SolveError> {
+        Ok(enhanced(parsed, 50))
     }
-    answer.record(&count_alight(&state));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::runner::assert_example;
+
+    const EXAMPLE: &str = "..#.#..#####.#.#.#.###.##.....###.##.#..###.####..#####..#....#..#..##..###..######.###...####..#..#####..##..#.#####...##.#.#..#.##..#.#......#.###.######.###.####...#.##.##..#..#..#####.....#.#....###..#.##......#.....#..#..#..##..#...##.######.####.####.#.#...#.......#..#.#.#...####.##.#......#..#...##.#.##..#...##.#.##..###.#......#.#.......#.#.#.####.###.##...#.....####.#..#..#.##.#....##..#.####....##...##..#...#......#.#.......#.......##..####..#...#.#.#...##..#.#..###..#####........#..####......#..#
 
-    answer
+#..#.
+#....
+##..#
+..#..
+..###";
+
+    #[test]
+    fn example() {
+        assert_example::<Day20>(EXAMPLE, Some("35"), Some("3351"));
+    }
 }
@@ -1,6 +1,8 @@
-use crate::utils::Answer;
+use crate::utils::parse::{Pattern, Section};
+use crate::utils::{Day, SolveError};
 use itertools::Itertools;
 use std::convert::TryInto;
+use std::fmt::Display;
 
 fn toggle(n: usize) -> usize {
     match n {
@@ -9,8 +11,8 @@ fn toggle(n: usize) -> usize {
     }
 }
 
-fn to_array<T, const N: usize>(v: Vec<T>) -> [T; N] {
-    v.try_into().unwrap_or_else(|_v: Vec<T>| panic!(":("))
+fn to_array<T, const N: usize>(v: Vec<T>) -> Option<[T; N]> {
+    v.try_into().ok()
 }
 
 fn play(
@@ -21,7 +23,7 @@ fn play(
 ) -> ([usize; 2], [i64; 2]) {
     let to = positions[player] + steps;
     if to > 10 {
-        if to % 10 == 0 {
+        if to.is_multiple_of(10) {
             positions[player] = 10;
         } else {
             positions[player] = to % 10;
@@ -71,57 +73,84 @@ fn quantum_game(
     )
 }
 
-pub fn day21(input: String) -> Answer {
-    let mut answer = Answer::default();
+pub struct Day21;
+
+impl Day for Day21 {
+    type Parsed = [usize; 2];
+
     //parse inputs
-    let positions: [usize; 2] = to_array(
-        input
-            .lines()
-            .map(|l| l.chars().last().unwrap().to_digit(10).unwrap() as usize)
-            .collect(),
-    );
+    fn parse(input: String) -> Result<[usize; 2], SolveError> {
+        let re = Pattern::new(
+            r"Player \d+ starting position: (\d+)",
+            "Player n starting position: p",
+        );
+
+        to_array(
+            re.parse_lines(&Section::whole(&input), |f| match f.get(1)? {
+                p @ 1..=10 => Ok(p),
+                p => Err(f.err(1, format!("Position {} is off the board", p))),
+            })?,
+        )
+        .ok_or_else(|| SolveError::parse("Expected starting positions for two players"))
+    }
 
     //part 1: multiply loser's score by number of rolls on the deterministic dice
-    let mut p1_positions = positions.clone();
-    let mut p1_scores: [i64; 2] = [0, 0];
-    let mut p1 = 0;
-    let mut player = 0;
-    let det_rolls = (1..101).cycle();
-    let mut rolled = 0;
-
-    for rs in det_rolls.chunks(3).into_iter() {
-        let steps: Vec<usize> = rs.collect();
-        rolled += 3;
-        (p1_positions, p1_scores) =
-            play(p1_positions, p1_scores, player, steps.iter().sum::<usize>());
-        if p1_scores[player] >= 1000 {
+    fn part1(positions: &[usize; 2]) -> Result<impl Display, SolveError> {
+        let mut p1_positions = *positions;
+        let mut p1_scores: [i64; 2] = [0, 0];
+        let mut p1 = 0;
+        let mut player = 0;
+        let det_rolls = (1..101).cycle();
+        let mut rolled = 0;
+
+        for rs in det_rolls.chunks(3).into_iter() {
+            let steps: Vec<usize> = rs.collect();
+            rolled += 3;
+            (p1_positions, p1_scores) =
+                play(p1_positions, p1_scores, player, steps.iter().sum::<usize>());
+            if p1_scores[player] >= 1000 {
+                player = toggle(player);
+                p1 = p1_scores[player] * rolled;
+                break;
+            }
             player = toggle(player);
-            p1 = p1_scores[player] * rolled;
-            break;
         }
-        player = toggle(player);
-    }
 
-    answer.record(&p1);
+        Ok(p1)
+    }
 
     //part 2: Produce a tree of universes and count the winners wins
-    let roll_matrix: Vec<(usize, usize)> = (1..4)
-        .cartesian_product(1..4)
-        .cartesian_product(1..4)
-        .map(|((a, b), c)| a + b + c)
-        .counts()
-        .into_iter()
-        .collect();
-
-    let res = quantum_game(
-        &roll_matrix,
-        (0, 1),
-        1,
-        positions,
-        [0, -(positions[1] as i64)],
-    );
-
-    answer.record(&usize::max(res[0], res[1]));
-
-    answer
+    fn part2(positions: &[usize; 2]) -> Result<impl Display, SolveError> {
+        let roll_matrix: Vec<(usize, usize)> = (1..4)
+            .cartesian_product(1..4)
+            .cartesian_product(1..4)
+            .map(|((a, b), c)| a + b + c)
+            .counts()
+            .into_iter()
+            .collect();
+
+        let res = quantum_game(
+            &roll_matrix,
+            (0, 1),
+            1,
+            *positions,
+            [0, -(positions[1] as i64)],
+        );
+
+        Ok(usize::max(res[0], res[1]))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::runner::assert_example;
+
+    const EXAMPLE: &str = "Player 1 starting position: 4
+Player 2 starting position: 8";
+
+    #[test]
+    fn example() {
+        assert_example::<Day21>(EXAMPLE, Some("739785"), Some("444356092776315"));
+    }
 }
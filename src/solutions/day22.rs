@@ -1,213 +1,186 @@
-use crate::utils::Answer;
-use regex::Regex;
+use crate::utils::geometry::{Cube, Cuboids};
+use crate::utils::parse::{Pattern, Section};
+use crate::utils::{Day, SolveError};
+use std::fmt::Display;
 
-#[derive(Debug)]
-enum Op {
-    On(Cuboid),
-    Off(Cuboid),
+#[derive(Debug, Clone)]
+pub enum Op {
+    On(Cube),
+    Off(Cube),
 }
 
 impl Op {
-    fn space(&self) -> Cuboid {
+    pub fn space(&self) -> Cube {
         match self {
-            Op::Off(c) => c.clone(),
-            Op::On(c) => c.clone(),
+            Op::Off(c) => *c,
+            Op::On(c) => *c,
         }
     }
 }
 
-#[derive(Debug, Clone)]
-struct Cuboid {
-    min_x: i64,
-    max_x: i64,
-    min_y: i64,
-    max_y: i64,
-    min_z: i64,
-    max_z: i64,
-}
-
-impl Cuboid {
-    fn new(x1: i64, x2: i64, y1: i64, y2: i64, z1: i64, z2: i64) -> Self {
-        Cuboid {
-            min_x: x1,
-            max_x: x2,
-            min_y: y1,
-            max_y: y2,
-            min_z: z1,
-            max_z: z2,
+//cuboids turned on by the instructions, in order
+fn reactor<'a>(instrs: impl Iterator<Item = &'a Op>) -> Cuboids<3> {
+    let mut on = Cuboids::new();
+    for i in instrs {
+        match i {
+            Op::On(c) => on.add(c),
+            Op::Off(c) => on.remove(c),
         }
     }
+    on
+}
 
-    fn vol(&self) -> i64 {
-        [
-            (self.min_x - 1, self.max_x),
-            (self.min_y - 1, self.max_y),
-            (self.min_z - 1, self.max_z),
-        ]
-        .iter()
-        .map(|(a, b)| i64::abs(b - a))
-        .product()
-    }
+pub struct Day22;
 
-    fn intersects(&self, oth: &Cuboid) -> bool {
-        let i = self.intersection(oth);
+impl Day for Day22 {
+    type Parsed = Vec<Op>;
 
-        (i.min_x <= i.max_x) && (i.min_y <= i.max_y) && (i.min_z <= i.max_z)
+    //parse instructions
+    fn parse(input: String) -> Result<Vec<Op>, SolveError> {
+        let re = Pattern::new(
+            r"(on|off) x=(-?\d+)\.\.(-?\d+),y=(-?\d+)\.\.(-?\d+),z=(-?\d+)\.\.(-?\d+)",
+            "on|off x=a..b,y=c..d,z=e..f",
+        );
+
+        re.parse_lines(&Section::whole(&input), |f| {
+            let x = Cube::new(
+                [f.get(2)?, f.get(4)?, f.get(6)?],
+                [f.get(3)?, f.get(5)?, f.get(7)?],
+            );
+            if f.str(1) == "on" {
+                Ok(Op::On(x))
+            } else {
+                Ok(Op::Off(x))
+            }
+        })
     }
 
-    fn intersection(&self, oth: &Cuboid) -> Cuboid {
-        Cuboid::new(
-            i64::max(self.min_x, oth.min_x),
-            i64::min(self.max_x, oth.max_x),
-            i64::max(self.min_y, oth.min_y),
-            i64::min(self.max_y, oth.max_y),
-            i64::max(self.min_z, oth.min_z),
-            i64::min(self.max_z, oth.max_z),
-        )
+    //get volume within the core, from just the instructions that reach it
+    fn part1(instrs: &Vec<Op>) -> Result<impl Display, SolveError> {
+        let core = Cube::new([-50; 3], [50; 3]);
+        let near = instrs.iter().filter(|i| i.space().intersects(&core));
+        Ok(reactor(near).clip(&core).volume())
     }
 
-    //returns non-overlapping sections of self with a different cuboid
-    fn atomize(&self, neg: &Cuboid) -> Vec<Cuboid> {
-        let mut res = vec![];
-
-        //front, back
-        if self.max_x > neg.max_x {
-            res.push(Cuboid::new(
-                neg.max_x + 1,
-                self.max_x,
-                self.min_y,
-                self.max_y,
-                self.min_z,
-                self.max_z,
-            ))
-        }
-        if self.min_x < neg.min_x {
-            res.push(Cuboid::new(
-                self.min_x,
-                neg.min_x - 1,
-                self.min_y,
-                self.max_y,
-                self.min_z,
-                self.max_z,
-            ))
-        }
-        //top, bottom
-        if self.max_y > neg.max_y {
-            res.push(Cuboid::new(
-                neg.min_x,
-                neg.max_x,
-                neg.max_y + 1,
-                self.max_y,
-                self.min_z,
-                self.max_z,
-            ))
-        }
-        if self.min_y < neg.min_y {
-            res.push(Cuboid::new(
-                neg.min_x,
-                neg.max_x,
-                self.min_y,
-                neg.min_y - 1,
-                self.min_z,
-                self.max_z,
-            ))
-        }
-        //sides
-        if self.max_z > neg.max_z {
-            res.push(Cuboid::new(
-                neg.min_x,
-                neg.max_x,
-                neg.min_y,
-                neg.max_y,
-                neg.max_z + 1,
-                self.max_z,
-            ))
-        }
-        if self.min_z < neg.min_z {
-            res.push(Cuboid::new(
-                neg.min_x,
-                neg.max_x,
-                neg.min_y,
-                neg.max_y,
-                self.min_z,
-                neg.min_z - 1,
-            ))
-        }
-
-        res
+    //get all volumes
+    fn part2(instrs: &Vec<Op>) -> Result<impl Display, SolveError> {
+        Ok(reactor(instrs.iter()).volume())
     }
 }
 
-fn add_cube(mut cuboids: Vec<Cuboid>, i: Op) -> Vec<Cuboid> {
-    let newboid = i.space();
-
-    let overlapping: Vec<Cuboid> = cuboids
-        .drain_filter(|oth| newboid.intersects(&oth))
-        .collect();
-
-    let atomized: Vec<Cuboid> = overlapping
-        .into_iter()
-        .map(|c| c.atomize(&c.intersection(&newboid)))
-        .flatten()
-        .collect();
-
-    cuboids.extend(atomized);
-
-    match i {
-        Op::Off(_) => (),
-        Op::On(n) => cuboids.push(n),
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::runner::assert_example;
+
+    const EXAMPLE: &str = "on x=10..12,y=10..12,z=10..12
+on x=11..13,y=11..13,z=11..13
+off x=9..11,y=9..11,z=9..11
+on x=10..10,y=10..10,z=10..10";
+
+    const LARGER: &str = "on x=-20..26,y=-36..17,z=-47..7
+on x=-20..33,y=-21..23,z=-26..28
+on x=-22..28,y=-29..23,z=-38..16
+on x=-46..7,y=-6..46,z=-50..-1
+on x=-49..1,y=-3..46,z=-24..28
+on x=2..47,y=-22..22,z=-23..27
+on x=-27..23,y=-28..26,z=-21..29
+on x=-39..5,y=-6..47,z=-3..44
+on x=-30..21,y=-8..43,z=-13..34
+on x=-22..26,y=-27..20,z=-29..19
+off x=-48..-32,y=26..41,z=-47..-37
+on x=-12..35,y=6..50,z=-50..-2
+off x=-48..-32,y=-32..-16,z=-15..-5
+on x=-18..26,y=-33..15,z=-7..46
+off x=-40..-22,y=-38..-28,z=23..41
+on x=-16..35,y=-41..10,z=-47..6
+off x=-32..-23,y=11..30,z=-14..3
+on x=-49..-5,y=-3..45,z=-29..18
+off x=18..30,y=-20..-8,z=-3..13
+on x=-41..9,y=-7..43,z=-33..15
+on x=-54112..-39298,y=-85059..-49293,z=-27449..7877
+on x=967..23432,y=45373..81175,z=27513..53682";
+
+    const PART2: &str = "on x=-5..47,y=-31..22,z=-19..33
+on x=-44..5,y=-27..21,z=-14..35
+on x=-49..-1,y=-11..42,z=-10..38
+on x=-20..34,y=-40..6,z=-44..1
+off x=26..39,y=40..50,z=-2..11
+on x=-41..5,y=-41..6,z=-36..8
+off x=-43..-33,y=-45..-28,z=7..25
+on x=-33..15,y=-32..19,z=-34..11
+off x=35..47,y=-46..-34,z=-11..5
+on x=-14..36,y=-6..44,z=-16..29
+on x=-57795..-6158,y=29564..72030,z=20435..90618
+on x=36731..105352,y=-21140..28532,z=16094..90401
+on x=30999..107136,y=-53464..15513,z=8553..71215
+on x=13528..83982,y=-99403..-27377,z=-24141..23996
+on x=-72682..-12347,y=18159..111354,z=7391..80950
+on x=-1060..80757,y=-65301..-20884,z=-103788..-16709
+on x=-83015..-9461,y=-72160..-8347,z=-81239..-26856
+on x=-52752..22273,y=-49450..9096,z=54442..119054
+on x=-29982..40483,y=-108474..-28371,z=-24328..38471
+on x=-4958..62750,y=40422..118853,z=-7672..65583
+on x=55694..108686,y=-43367..46958,z=-26781..48729
+on x=-98497..-18186,y=-63569..3412,z=1232..88485
+on x=-726..56291,y=-62629..13224,z=18033..85226
+on x=-110886..-34664,y=-81338..-8658,z=8914..63723
+on x=-55829..24974,y=-16897..54165,z=-121762..-28058
+on x=-65152..-11147,y=22489..91432,z=-58782..1780
+on x=-120100..-32970,y=-46592..27473,z=-11695..61039
+on x=-18631..37533,y=-124565..-50804,z=-35667..28308
+on x=-57817..18248,y=49321..117703,z=5745..55881
+on x=14781..98692,y=-1341..70827,z=15753..70151
+on x=-34419..55919,y=-19626..40991,z=39015..114138
+on x=-60785..11593,y=-56135..2999,z=-95368..-26915
+on x=-32178..58085,y=17647..101866,z=-91405..-8878
+on x=-53655..12091,y=50097..105568,z=-75335..-4862
+on x=-111166..-40997,y=-71714..2688,z=5609..50954
+on x=-16602..70118,y=-98693..-44401,z=5197..76897
+on x=16383..101554,y=4615..83635,z=-44907..18747
+off x=-95822..-15171,y=-19987..48940,z=10804..104439
+on x=-89813..-14614,y=16069..88491,z=-3297..45228
+on x=41075..99376,y=-20427..49978,z=-52012..13762
+on x=-21330..50085,y=-17944..62733,z=-112280..-30197
+on x=-16478..35915,y=36008..118594,z=-7885..47086
+off x=-98156..-27851,y=-49952..43171,z=-99005..-8456
+off x=2032..69770,y=-71013..4824,z=7471..94418
+on x=43670..120875,y=-42068..12382,z=-24787..38892
+off x=37514..111226,y=-45862..25743,z=-16714..54663
+off x=25699..97951,y=-30668..59918,z=-15349..69697
+off x=-44271..17935,y=-9516..60759,z=49131..112598
+on x=-61695..-5813,y=40978..94975,z=8655..80240
+off x=-101086..-9439,y=-7088..67543,z=33935..83858
+off x=18020..114017,y=-48931..32606,z=21474..89843
+off x=-77139..10506,y=-89994..-18797,z=-80..59318
+off x=8476..79288,y=-75520..11602,z=-96624..-24783
+on x=-47488..-1262,y=24338..100707,z=16292..72967
+off x=-84341..13987,y=2429..92914,z=-90671..-1318
+off x=-37810..49457,y=-71013..-7894,z=-105357..-13188
+off x=-27365..46395,y=31009..98017,z=15428..76570
+off x=-70369..-16548,y=22648..78696,z=-1892..86821
+on x=-53470..21291,y=-120233..-33476,z=-44150..38147
+off x=-93533..-4276,y=-16170..68771,z=-104985..-24507";
+
+    #[test]
+    fn example() {
+        assert_example::<Day22>(EXAMPLE, Some("39"), Some("39"));
     }
 
-    cuboids
-}
-
-fn in_core(cuboid: &Cuboid) -> bool {
-    cuboid.min_x >= -50
-        && cuboid.max_x <= 50
-        && cuboid.min_y >= -50
-        && cuboid.max_y <= 50
-        && cuboid.min_z >= -50
-        && cuboid.max_z <= 50
-}
-
-pub fn day22(input: String) -> Answer {
-    let mut answer = Answer::default();
-    let re =
-        Regex::new(r"(on|off) x=(-?\d+)..(-?\d+),y=(-?\d+)..(-?\d+),z=(-?\d+)..(-?\d+)").unwrap();
-
-    //parse instructions
-    let instrs: Vec<Op> = re
-        .captures_iter(&input)
-        .map(|c| {
-            let x = Cuboid::new(
-                c[2].parse().unwrap(),
-                c[3].parse().unwrap(),
-                c[4].parse().unwrap(),
-                c[5].parse().unwrap(),
-                c[6].parse().unwrap(),
-                c[7].parse().unwrap(),
-            );
-            if &c[1] == "on" {
-                Op::On(x)
-            } else {
-                Op::Off(x)
-            }
-        })
-        .collect();
-
-    //construct set of on cubes
-    let mut ons: Vec<Cuboid> = vec![];
-    for i in instrs {
-        ons = add_cube(ons, i);
+    #[test]
+    fn larger_example() {
+        assert_example::<Day22>(LARGER, Some("590784"), None);
     }
 
-    //get volumes of inner set
-    let p1: i64 = ons.iter().filter(|c| in_core(c)).map(|c| c.vol()).sum();
-    answer.record(&p1);
-
-    //get all volumes
-    let p2: i64 = ons.iter().map(|c| c.vol()).sum();
-    answer.record(&p2);
+    #[test]
+    fn part2_example() {
+        assert_example::<Day22>(PART2, Some("474140"), Some("2758514936282235"));
+    }
 
-    answer
+    //only the cubes within -50..50 count for part 1, even of steps reaching past it
+    #[test]
+    fn clipped_to_core() {
+        let steps = "on x=-60..-40,y=0..0,z=0..0\noff x=-45..-45,y=0..0,z=0..0";
+        assert_example::<Day22>(steps, Some("10"), Some("20"));
+    }
 }
@@ -1,48 +1,49 @@
+use crate::runner::run;
 use crate::utils::Solution;
-mod day01;
-mod day02;
-mod day03;
-mod day04;
-mod day05;
-mod day06;
-mod day07;
-mod day08;
-mod day09;
-mod day10;
-mod day11;
-mod day12;
-mod day13;
-mod day14;
-mod day15;
-mod day16;
-mod day17;
-mod day18;
-mod day19;
-mod day20;
-mod day21;
-mod day22;
+pub mod day01;
+pub mod day02;
+pub mod day03;
+pub mod day04;
+pub mod day05;
+pub mod day06;
+pub mod day07;
+pub mod day08;
+pub mod day09;
+pub mod day10;
+pub mod day11;
+pub mod day12;
+pub mod day13;
+pub mod day14;
+pub mod day15;
+pub mod day16;
+pub mod day17;
+pub mod day18;
+pub mod day19;
+pub mod day20;
+pub mod day21;
+pub mod day22;
 
 pub const DAYS: [Solution; 22] = [
-    day01::day01,
-    day02::day02,
-    day03::day03,
-    day04::day04,
-    day05::day05,
-    day06::day06,
-    day07::day07,
-    day08::day08,
-    day09::day09,
-    day10::day10,
-    day11::day11,
-    day12::day12,
-    day13::day13,
-    day14::day14,
-    day15::day15,
-    day16::day16,
-    day17::day17,
-    day18::day18,
-    day19::day19,
-    day20::day20,
-    day21::day21,
-    day22::day22,
+    run::<day01::Day01>,
+    run::<day02::Day02>,
+    run::<day03::Day03>,
+    run::<day04::Day04>,
+    run::<day05::Day05>,
+    run::<day06::Day06>,
+    run::<day07::Day07>,
+    run::<day08::Day08>,
+    run::<day09::Day09>,
+    run::<day10::Day10>,
+    run::<day11::Day11>,
+    run::<day12::Day12>,
+    run::<day13::Day13>,
+    run::<day14::Day14>,
+    run::<day15::Day15>,
+    run::<day16::Day16>,
+    run::<day17::Day17>,
+    run::<day18::Day18>,
+    run::<day19::Day19>,
+    run::<day20::Day20>,
+    run::<day21::Day21>,
+    run::<day22::Day22>,
 ];
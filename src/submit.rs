@@ -0,0 +1,408 @@
+use crate::client::Client;
+use crate::runner::Inputs;
+use crate::utils::now;
+use regex::Regex;
+use std::fmt::{Display, Formatter};
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::str::FromStr;
+
+//Types
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Outcome {
+    Right,
+    Wrong,
+    TooHigh,
+    TooLow,
+    RateLimited,
+    AlreadySolved,
+    Unknown,
+}
+
+impl Outcome {
+    //whether the site judged the answer incorrect
+    pub fn is_wrong(&self) -> bool {
+        matches!(self, Outcome::Wrong | Outcome::TooHigh | Outcome::TooLow)
+    }
+
+    fn key(&self) -> &'static str {
+        match self {
+            Outcome::Right => "right",
+            Outcome::Wrong => "wrong",
+            Outcome::TooHigh => "too-high",
+            Outcome::TooLow => "too-low",
+            Outcome::RateLimited => "rate-limited",
+            Outcome::AlreadySolved => "already-solved",
+            Outcome::Unknown => "unknown",
+        }
+    }
+}
+
+impl Display for Outcome {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Outcome::Right => write!(f, "the right answer!"),
+            Outcome::Wrong => write!(f, "not the right answer"),
+            Outcome::TooHigh => write!(f, "not the right answer (too high)"),
+            Outcome::TooLow => write!(f, "not the right answer (too low)"),
+            Outcome::RateLimited => write!(f, "not checked: answered too recently"),
+            Outcome::AlreadySolved => write!(f, "not checked: that part is already solved"),
+            Outcome::Unknown => write!(f, "not recognised in the response"),
+        }
+    }
+}
+
+impl FromStr for Outcome {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        [
+            Outcome::Right,
+            Outcome::Wrong,
+            Outcome::TooHigh,
+            Outcome::TooLow,
+            Outcome::RateLimited,
+            Outcome::AlreadySolved,
+            Outcome::Unknown,
+        ]
+        .into_iter()
+        .find(|o| o.key() == s)
+        .ok_or_else(|| format!("Unknown submission outcome {}", s))
+    }
+}
+
+//Structs
+
+//one submission: when it was made, what the site said, and how long it asked us to wait afterwards
+#[derive(Debug, Clone, PartialEq)]
+pub struct Attempt {
+    pub time: u64,
+    pub day: usize,
+    pub part: usize,
+    pub outcome: Outcome,
+    pub wait: u64,
+    pub answer: String,
+}
+
+impl Display for Attempt {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{} {} {} {} {} {}",
+            self.time,
+            self.day,
+            self.part,
+            self.outcome.key(),
+            self.wait,
+            self.answer
+        )
+    }
+}
+
+//Functions
+
+//the submission history lives beside the inputs it was made for
+pub fn history_path(inputs: &Inputs) -> String {
+    format!("{}/submissions.txt", inputs.dir.trim_end_matches('/'))
+}
+
+//parse a history: one `<time> <day> <part> <outcome> <wait> <answer>` per line
+pub fn parse_history(input: &str) -> Result<Vec<Attempt>, String> {
+    let mut history = vec![];
+
+    for (n, l) in input.lines().enumerate() {
+        if l.trim().is_empty() {
+            continue;
+        }
+
+        let mut sp = l.trim().splitn(6, ' ');
+        let attempt = (|| {
+            Some(Attempt {
+                time: sp.next()?.parse().ok()?,
+                day: sp.next()?.parse().ok()?,
+                part: sp.next()?.parse().ok()?,
+                outcome: sp.next()?.parse().ok()?,
+                wait: sp.next()?.parse().ok()?,
+                answer: sp.next()?.to_string(),
+            })
+        })();
+
+        match attempt {
+            Some(a) => history.push(a),
+            None => return Err(format!("Invalid submission on line {}: `{}`", n + 1, l)),
+        }
+    }
+
+    Ok(history)
+}
+
+//a missing history file just means nothing has been submitted yet
+pub fn load_history(path: &str) -> Result<Vec<Attempt>, String> {
+    match fs::read_to_string(path) {
+        Ok(s) => parse_history(&s),
+        Err(_) => Ok(vec![]),
+    }
+}
+
+pub fn record(path: &str, attempt: &Attempt) -> Result<(), String> {
+    OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(path)
+        .and_then(|mut f| writeln!(f, "{}", attempt))
+        .map_err(|e| format!("Could not record submission in {}: {}", path, e))
+}
+
+//work out what the site made of an answer, and how many seconds it wants us to wait before the next one
+pub fn parse_response(page: &str) -> (Outcome, u64) {
+    let outcome = if page.contains("That's the right answer") {
+        Outcome::Right
+    } else if page.contains("You gave an answer too recently") {
+        Outcome::RateLimited
+    } else if page.contains("That's not the right answer") {
+        if page.contains("too high") {
+            Outcome::TooHigh
+        } else if page.contains("too low") {
+            Outcome::TooLow
+        } else {
+            Outcome::Wrong
+        }
+    } else if page.contains("Did you already complete it") {
+        Outcome::AlreadySolved
+    } else {
+        Outcome::Unknown
+    };
+
+    //rate limits say "You have 1m 23s left to wait", wrong answers "please wait 5 minutes"
+    let left = Regex::new(r"You have (?:(\d+)m )?(\d+)s left to wait").unwrap();
+    let mins = Regex::new(r"(?i)please wait (one|\d+) minutes?").unwrap();
+    let num = |c: Option<regex::Match>| c.and_then(|m| m.as_str().parse::<u64>().ok());
+
+    let wait = if let Some(c) = left.captures(page) {
+        num(c.get(1)).unwrap_or(0) * 60 + num(c.get(2)).unwrap_or(0)
+    } else if let Some(c) = mins.captures(page) {
+        num(c.get(1)).unwrap_or(1) * 60
+    } else {
+        0
+    };
+
+    (outcome, wait)
+}
+
+//refuse submissions that can't help: during a cooldown, for solved parts, and for answers already known to be wrong
+pub fn vet(
+    history: &[Attempt],
+    day: usize,
+    part: usize,
+    answer: &str,
+    now: u64,
+) -> Result<(), String> {
+    if let Some(until) = history
+        .iter()
+        .map(|a| a.time + a.wait)
+        .max()
+        .filter(|u| *u > now)
+    {
+        return Err(format!(
+            "Still cooling down from an earlier submission; wait {}s",
+            until - now
+        ));
+    }
+
+    let n: Option<i64> = answer.parse().ok();
+    for a in history.iter().filter(|a| a.day == day && a.part == part) {
+        let known = match (a.outcome, n, a.answer.parse::<i64>()) {
+            (Outcome::Right, _, _) => return Err(format!("Already solved with {}", a.answer)),
+            (o, _, _) if o.is_wrong() && a.answer == answer => true,
+            (Outcome::TooHigh, Some(n), Ok(h)) => n >= h,
+            (Outcome::TooLow, Some(n), Ok(l)) => n <= l,
+            _ => false,
+        };
+
+        if known {
+            return Err(format!(
+                "{} can't be right: {} was {}",
+                answer, a.answer, a.outcome
+            ));
+        }
+    }
+
+    Ok(())
+}
+
+//submit an answer unless the history rules it out, recording what the site said
+pub fn submit(
+    client: &Client,
+    inputs: &Inputs,
+    day: usize,
+    part: usize,
+    answer: &str,
+) -> Result<Outcome, String> {
+    let answer = answer.trim();
+    if answer.is_empty() || answer.contains(char::is_whitespace) {
+        return Err(format!(
+            "{:?} is not a single answer that can be submitted",
+            answer
+        ));
+    }
+
+    let path = history_path(inputs);
+    vet(&load_history(&path)?, day, part, answer, now())?;
+
+    let (outcome, wait) = parse_response(&client.answer(day, part, answer)?);
+    fs::create_dir_all(&inputs.dir)
+        .map_err(|e| format!("Could not create {}: {}", inputs.dir, e))?;
+    record(
+        &path,
+        &Attempt {
+            time: now(),
+            day,
+            part,
+            outcome,
+            wait,
+            answer: answer.to_string(),
+        },
+    )?;
+
+    Ok(outcome)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::client::stub_server;
+    use crate::runner::temp_inputs;
+
+    fn attempt(time: u64, part: usize, outcome: Outcome, wait: u64, answer: &str) -> Attempt {
+        Attempt {
+            time,
+            day: 3,
+            part,
+            outcome,
+            wait,
+            answer: answer.to_string(),
+        }
+    }
+
+    #[test]
+    fn responses() {
+        assert_eq!(
+            parse_response("<p>That's the right answer! You are one gold star closer.</p>"),
+            (Outcome::Right, 0)
+        );
+        assert_eq!(
+            parse_response(
+                "That's not the right answer. Please wait one minute before trying again."
+            ),
+            (Outcome::Wrong, 60)
+        );
+        assert_eq!(
+            parse_response(
+                "That's not the right answer; your answer is too high. Please wait 5 minutes"
+            ),
+            (Outcome::TooHigh, 300)
+        );
+        assert_eq!(
+            parse_response("That's not the right answer; your answer is too low."),
+            (Outcome::TooLow, 0)
+        );
+        assert_eq!(
+            parse_response("You gave an answer too recently. You have 1m 23s left to wait."),
+            (Outcome::RateLimited, 83)
+        );
+        assert_eq!(
+            parse_response("You gave an answer too recently. You have 9s left to wait."),
+            (Outcome::RateLimited, 9)
+        );
+        assert_eq!(
+            parse_response("Did you already complete it?"),
+            (Outcome::AlreadySolved, 0)
+        );
+        assert_eq!(parse_response("<html></html>"), (Outcome::Unknown, 0));
+    }
+
+    #[test]
+    fn vetting() {
+        let history = vec![
+            attempt(1000, 1, Outcome::TooHigh, 60, "500"),
+            attempt(1100, 1, Outcome::TooLow, 60, "100"),
+            attempt(1200, 1, Outcome::Wrong, 60, "abc"),
+        ];
+
+        assert!(vet(&history, 3, 1, "300", 1230)
+            .unwrap_err()
+            .starts_with("Still cooling down"));
+        assert_eq!(vet(&history, 3, 1, "300", 1260), Ok(()));
+        assert!(vet(&history, 3, 1, "abc", 1260).is_err());
+        assert_eq!(
+            vet(&history, 3, 1, "600", 1260),
+            Err(String::from(
+                "600 can't be right: 500 was not the right answer (too high)"
+            ))
+        );
+        assert!(vet(&history, 3, 1, "100", 1260).is_err());
+        assert!(vet(&history, 3, 1, "50", 1260).is_err());
+
+        //other parts and days aren't ruled out
+        assert_eq!(vet(&history, 3, 2, "600", 1260), Ok(()));
+        assert_eq!(vet(&history, 4, 1, "600", 1260), Ok(()));
+
+        let solved = vec![attempt(1000, 2, Outcome::Right, 0, "42")];
+        assert_eq!(
+            vet(&solved, 3, 2, "43", 2000),
+            Err(String::from("Already solved with 42"))
+        );
+    }
+
+    #[test]
+    fn history_round_trip() {
+        let history = vec![
+            attempt(1000, 1, Outcome::TooHigh, 60, "500"),
+            attempt(1100, 2, Outcome::RateLimited, 83, "1,2,3"),
+            attempt(1200, 2, Outcome::AlreadySolved, 0, "x"),
+        ];
+        let text: String = history.iter().map(|a| format!("{}\n\n", a)).collect();
+        assert_eq!(parse_history(&text), Ok(history));
+        assert_eq!(
+            parse_history("1000 3 1 right 0 42\n1000 3 x right 0 42"),
+            Err(String::from(
+                "Invalid submission on line 2: `1000 3 x right 0 42`"
+            ))
+        );
+        assert!(parse_history("1000 3 1 maybe 0 42").is_err());
+    }
+
+    #[test]
+    fn submits_and_records() {
+        let (url, requests) = stub_server(
+            "That's not the right answer; your answer is too low. Please wait one minute.",
+            1,
+        );
+        let c = Client::new(&url, "token");
+        let inputs = temp_inputs("submit");
+
+        assert_eq!(submit(&c, &inputs, 3, 2, " 42\n"), Ok(Outcome::TooLow));
+        assert_eq!(
+            requests.recv().unwrap(),
+            "POST /day/3/answer HTTP/1.1 level=2&answer=42"
+        );
+
+        let history = load_history(&history_path(&inputs)).unwrap();
+        assert_eq!(history.len(), 1);
+        assert_eq!(
+            (
+                history[0].outcome,
+                history[0].wait,
+                history[0].answer.as_str()
+            ),
+            (Outcome::TooLow, 60, "42")
+        );
+
+        //the recorded cooldown stops the next one before it reaches the server
+        assert!(submit(&c, &inputs, 3, 2, "43").is_err());
+        assert!(submit(&c, &inputs, 3, 2, "4 3").is_err());
+        assert!(requests.try_recv().is_err());
+        let _ = fs::remove_dir_all(&inputs.dir);
+    }
+}
@@ -1,33 +1,151 @@
+use std::cell::RefCell;
 use std::cmp::Eq;
 use std::collections::{HashMap, HashSet, VecDeque};
 use std::fmt::Debug;
-use std::fmt::{Display, Formatter, Result};
+use std::fmt::{self, Display, Formatter};
 use std::hash::Hash;
 use std::str::FromStr;
-use std::time::Instant;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
 
 use itertools::Itertools;
 
+use crate::alloc::Memory;
+
+pub mod automaton;
+pub mod dense;
+pub mod geometry;
+pub mod letters;
+pub mod parse;
+pub mod pathfinding;
+
+pub use dense::DenseGrid;
+
 //Types
 
-pub type Solution = fn(String) -> Answer; // Solution functions
+pub type Solution = fn(String, Parts) -> Result<Answer, SolveError>; // Day::run for each day
 
 type DisplayableRef<'a> = &'a dyn Display; // Shorthand for Answer struct stuff
 
+//Traits
+
+//a day's puzzle: the input is parsed once, then each part is solved from the parsed value
+pub trait Day {
+    type Parsed;
+
+    fn parse(input: String) -> Result<Self::Parsed, SolveError>;
+    fn part1(parsed: &Self::Parsed) -> Result<impl Display, SolveError>;
+    fn part2(parsed: &Self::Parsed) -> Result<impl Display, SolveError>;
+
+    //named results beyond the two parts, such as bonus questions on some puzzle variants
+    fn extras(_parsed: &Self::Parsed) -> Result<Vec<(String, String)>, SolveError> {
+        Ok(vec![])
+    }
+}
+
 //Functions
 
-pub fn simple_parse<T>(input: String, separator: &str) -> Vec<T>
+thread_local! {
+    static CHECKPOINTS: RefCell<Vec<(String, Instant)>> = const { RefCell::new(vec![]) };
+}
+
+//mark the end of a named step within the phase being run, so it's timed separately in the answer
+pub fn checkpoint(name: &str) {
+    CHECKPOINTS.with(|c| c.borrow_mut().push((name.to_string(), Instant::now())));
+}
+
+//the steps marked since a phase started, each timed from the end of the step before it
+pub fn take_checkpoints(start: Instant) -> Vec<(String, Duration)> {
+    let mut last = start;
+    CHECKPOINTS.with(|c| {
+        c.borrow_mut()
+            .drain(..)
+            .map(|(name, t)| {
+                let d = t.saturating_duration_since(last);
+                last = t;
+                (name, d)
+            })
+            .collect()
+    })
+}
+
+//seconds since the unix epoch, for stamping what's recorded in the input directory
+pub fn now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|t| t.as_secs())
+        .unwrap_or(0)
+}
+
+//bytes in the largest unit that keeps the number above 1
+pub fn fmt_bytes(b: usize) -> String {
+    let units = ["B", "KiB", "MiB", "GiB"];
+    let mut n = b as f64;
+    let mut u = 0;
+    while n >= 1024.0 && u < units.len() - 1 {
+        n /= 1024.0;
+        u += 1;
+    }
+
+    match u {
+        0 => format!("{} B", b),
+        _ => format!("{:.2} {}", n, units[u]),
+    }
+}
+
+fn capitalise(s: &str) -> String {
+    let mut cs = s.chars();
+    match cs.next() {
+        Some(c) => c.to_uppercase().chain(cs).collect(),
+        None => String::new(),
+    }
+}
+
+pub fn simple_parse<T>(input: String, separator: &str) -> Result<Vec<T>, SolveError>
 where
     T: FromStr,
-    <T as FromStr>::Err: Debug,
+    <T as FromStr>::Err: Display,
 {
-    input
-        .split(separator)
-        .map(|x| {
-            x.parse()
-                .expect("Error parsing input - are you sure it's a simple list?")
-        })
-        .collect::<Vec<T>>()
+    let mut offset = 0;
+    let mut res = vec![];
+
+    for x in input.split(separator) {
+        match x.parse() {
+            Ok(v) => res.push(v),
+            Err(e) => {
+                let (line, col) = line_col(&input, offset);
+                return Err(SolveError::parse(format!(
+                    "Could not parse `{}` in simple list: {}",
+                    x, e
+                ))
+                .at(line, col));
+            }
+        }
+        offset += x.len() + separator.len();
+    }
+
+    Ok(res)
+}
+
+//1-based line and column of a byte offset into the input
+pub fn line_col(input: &str, offset: usize) -> (usize, usize) {
+    let before = &input[..offset];
+    let line_start = before.rfind('\n').map(|i| i + 1).unwrap_or(0);
+
+    (
+        before.matches('\n').count() + 1,
+        before[line_start..].chars().count() + 1,
+    )
+}
+
+//parse a value found on the given (0-indexed) line of the input
+pub fn parse_on<T>(s: &str, line: usize) -> Result<T, SolveError>
+where
+    T: FromStr,
+    <T as FromStr>::Err: Display,
+{
+    s.trim().parse().map_err(|e| {
+        SolveError::parse(format!("Could not parse `{}`: {}", s.trim(), e)).at_line(line + 1)
+    })
 }
 
 //binary string to integer
@@ -40,120 +158,263 @@ pub fn bit_to_n(slice: &[usize]) -> usize {
 
 //Structs
 
-pub struct Answer {
-    start: Instant,
-    parsed: Option<Instant>,
-    part1: Option<String>,
-    part2: Option<String>,
-    time1: Option<Instant>,
-    time2: Option<Instant>,
+//which parts of a day to run
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Parts {
+    Both,
+    One,
+    Two,
 }
 
-impl Default for Answer {
-    fn default() -> Self {
-        Self {
-            start: Instant::now(),
-            parsed: None,
-            part1: None,
-            part2: None,
-            time1: None,
-            time2: None,
+impl Parts {
+    pub fn has(self, part: usize) -> bool {
+        matches!(
+            (self, part),
+            (Parts::Both, _) | (Parts::One, 1) | (Parts::Two, 2)
+        )
+    }
+}
+
+impl FromStr for Parts {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim() {
+            "1" => Ok(Parts::One),
+            "2" => Ok(Parts::Two),
+            "both" => Ok(Parts::Both),
+            p => Err(format!("Unknown part {} (expected 1, 2 or both)", p)),
         }
     }
 }
 
-impl Display for Answer {
-    fn fmt(&self, f: &mut Formatter<'_>) -> Result {
-        //parsing time
-        let (parsed, t_last) = match self.parsed {
-            None => (String::from("Parsing not yet implemented\n"), self.start),
-            Some(t) => (
-                format!(
-                    "Parsed input in {:?}\n",
-                    t.saturating_duration_since(self.start)
-                ),
-                t,
-            ),
-        };
+//how long a phase took, split into any checkpoints it marked along the way,
+//and what it allocated if allocations are being counted
+#[derive(Debug, Clone, PartialEq)]
+pub struct Timing {
+    pub phase: Phase,
+    pub time: Duration,
+    pub checkpoints: Vec<(String, Duration)>,
+    pub memory: Option<Memory>,
+}
 
-        //parts
-        let p1 = match &self.part1 {
-            None => String::from("Part 1 not yet implemented.\n"),
-            Some(r) => format!("Part 1 Result: {}\n", r),
-        };
-        let p2 = match &self.part2 {
-            None => String::from("Part 2 not yet implemented.\n"),
-            Some(r) => format!("Part 2 Result: {}\n", r),
-        };
+//the named results of a run in the order they were recorded, and how long each phase took.
+//parts that weren't run have no result or timing
+#[derive(Default)]
+pub struct Answer {
+    results: Vec<(String, String)>,
+    timings: Vec<Timing>,
+}
+
+impl Display for Answer {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        //results
+        for (name, r) in &self.results {
+            writeln!(f, "{} Result: {}", name, r)?;
+        }
 
-        //times
-        let ts = match (self.time1, self.time2) {
-            (Some(t), None) => format!(
-                "Part 1 Runtime: {:?}\n",
-                t.saturating_duration_since(t_last)
-            ),
-            (Some(t1), Some(t2)) => {
-                if t1 == t2 {
-                    format!(
-                        "Part 1 & 2 Runtime: {:?}\n",
-                        t1.saturating_duration_since(t_last)
-                    )
-                } else {
-                    format!(
-                        "Part 1 Runtime: {:?}\nPart 2 Runtime: {:?}\n",
-                        t1.saturating_duration_since(t_last),
-                        t2.saturating_duration_since(t1)
-                    )
-                }
+        //times, with any checkpoints under their phase
+        for t in &self.timings {
+            match t.phase {
+                Phase::Parse => write!(f, "Parsed input in {:?}", t.time)?,
+                p => write!(f, "{} Runtime: {:?}", capitalise(&p.to_string()), t.time)?,
             }
-            _ => String::from(""),
-        };
+            match t.memory {
+                Some(m) => writeln!(
+                    f,
+                    " (peak {}, {} allocated)",
+                    fmt_bytes(m.peak),
+                    fmt_bytes(m.total)
+                )?,
+                None => writeln!(f)?,
+            }
+            for (name, ct) in &t.checkpoints {
+                writeln!(f, "  {}: {:?}", name, ct)?;
+            }
+        }
 
         //overall time
-        let overall = format!(
-            "Overall runtime: {:?}",
-            self.time2
-                .or(self.time1)
-                .or(Some(t_last))
-                .unwrap()
-                .saturating_duration_since(self.start)
-        );
+        write!(f, "Overall runtime: {:?}", self.overall_time())
+    }
+}
+
+impl Answer {
+    pub fn record(&mut self, name: &str, res: DisplayableRef) {
+        self.results.push((name.to_string(), res.to_string()));
+    }
+
+    pub fn record_time(&mut self, timing: Timing) {
+        self.timings.push(timing);
+    }
+
+    pub fn results(&self) -> &[(String, String)] {
+        &self.results
+    }
+
+    pub fn timings(&self) -> &[Timing] {
+        &self.timings
+    }
+
+    pub fn result(&self, name: &str) -> Option<&str> {
+        self.results
+            .iter()
+            .find(|(n, _)| n == name)
+            .map(|(_, r)| r.as_str())
+    }
+
+    pub fn time(&self, phase: Phase) -> Option<Duration> {
+        self.timings
+            .iter()
+            .find(|t| t.phase == phase)
+            .map(|t| t.time)
+    }
+
+    pub fn part1(&self) -> Option<&str> {
+        self.result("Part 1")
+    }
+
+    pub fn part2(&self) -> Option<&str> {
+        self.result("Part 2")
+    }
+
+    //results other than the two parts, such as bonus questions
+    pub fn extras(&self) -> impl Iterator<Item = &(String, String)> {
+        self.results
+            .iter()
+            .filter(|(n, _)| n != "Part 1" && n != "Part 2")
+    }
 
-        write!(f, "{}{}{}{}{}", p1, p2, parsed, ts, overall)
+    pub fn parse_time(&self) -> Option<Duration> {
+        self.time(Phase::Parse)
+    }
+
+    pub fn part1_time(&self) -> Option<Duration> {
+        self.time(Phase::Part1)
+    }
+
+    pub fn part2_time(&self) -> Option<Duration> {
+        self.time(Phase::Part2)
+    }
+
+    pub fn overall_time(&self) -> Duration {
+        self.timings.iter().map(|t| t.time).sum()
+    }
+
+    pub fn memory(&self, phase: Phase) -> Option<Memory> {
+        self.timings
+            .iter()
+            .find(|t| t.phase == phase)
+            .and_then(|t| t.memory)
+    }
+
+    //the largest peak of any phase and the total allocated by all of them
+    pub fn overall_memory(&self) -> Option<Memory> {
+        self.timings
+            .iter()
+            .map(|t| t.memory)
+            .reduce(|a, b| {
+                a.zip(b).map(|(a, b)| Memory {
+                    peak: a.peak.max(b.peak),
+                    total: a.total + b.total,
+                })
+            })
+            .flatten()
     }
 }
 
-impl<'a> Answer {
-    pub fn record_parsed(&mut self) {
-        self.parsed = Some(Instant::now());
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Phase {
+    Input,
+    Parse,
+    Part1,
+    Part2,
+    Extras,
+    //the day was abandoned for running too long
+    Timeout,
+}
+
+impl Display for Phase {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Phase::Input => write!(f, "input"),
+            Phase::Parse => write!(f, "parse"),
+            Phase::Part1 => write!(f, "part 1"),
+            Phase::Part2 => write!(f, "part 2"),
+            Phase::Extras => write!(f, "extras"),
+            Phase::Timeout => write!(f, "timeout"),
+        }
     }
+}
 
-    pub fn record(&mut self, res: DisplayableRef) {
-        match self.part1 {
-            None => {
-                self.part1 = Some(res.to_string());
-                self.time1 = Some(Instant::now());
-            }
-            Some(_) => match self.part2 {
-                None => {
-                    self.part2 = Some(res.to_string());
-                    self.time2 = Some(Instant::now());
-                }
-                Some(_) => panic!("Cannot write third part to an answer!"),
-            },
+//an error from running a solution, with the 1-based line and column of the offending input when known
+#[derive(Debug, Clone, PartialEq)]
+pub struct SolveError {
+    pub day: Option<usize>,
+    pub phase: Phase,
+    pub line: Option<usize>,
+    pub col: Option<usize>,
+    pub msg: String,
+}
+
+impl Display for SolveError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match (self.day, self.phase) {
+            (Some(d), Phase::Timeout) => write!(f, "Day {} TIMEOUT", d)?,
+            (None, Phase::Timeout) => write!(f, "TIMEOUT")?,
+            (Some(d), p) => write!(f, "Day {} {} error", d, p)?,
+            (None, p) => write!(f, "{} error", p)?,
         }
+        match (self.line, self.col) {
+            (Some(l), Some(c)) => write!(f, " at line {}, column {}", l, c)?,
+            (Some(l), None) => write!(f, " at line {}", l)?,
+            _ => (),
+        }
+        write!(f, ": {}", self.msg)
     }
+}
+
+impl std::error::Error for SolveError {}
 
-    pub fn record_both(&mut self, res1: DisplayableRef, res2: DisplayableRef) {
-        if self.part1.is_some() {
-            panic!("Cannot write both parts to partially recorded answer!")
+impl SolveError {
+    pub fn new(phase: Phase, msg: impl Into<String>) -> Self {
+        Self {
+            day: None,
+            phase,
+            line: None,
+            col: None,
+            msg: msg.into(),
         }
+    }
+
+    pub fn parse(msg: impl Into<String>) -> Self {
+        Self::new(Phase::Parse, msg)
+    }
+
+    pub fn at(mut self, line: usize, col: usize) -> Self {
+        self.line = Some(line);
+        self.col = Some(col);
+        self
+    }
+
+    pub fn at_line(mut self, line: usize) -> Self {
+        self.line = Some(line);
+        self
+    }
+
+    //for errors found in a section of input starting n lines in
+    pub fn offset_lines(mut self, n: usize) -> Self {
+        self.line = self.line.map(|l| l + n);
+        self
+    }
 
-        let i = Instant::now();
-        self.part1 = Some(res1.to_string());
-        self.part2 = Some(res2.to_string());
-        self.time1 = Some(i);
-        self.time2 = Some(i);
+    pub fn on_day(mut self, day: usize) -> Self {
+        self.day = Some(day);
+        self
+    }
+
+    pub fn during(mut self, phase: Phase) -> Self {
+        self.phase = phase;
+        self
     }
 }
 
@@ -263,21 +524,117 @@ impl Point for Pt3d {
     }
 
     fn neighbours_all(&self) -> Vec<Self> {
-        (-1..2)
-            .permutations(3)
-            .filter(|n| n.iter().filter(|e| **e == 0).count() != 3)
-            .map(|o| self.add(&(o[0], o[1], o[2])))
+        PtN::from(*self)
+            .neighbours_all()
+            .into_iter()
+            .map(Pt3d::from)
             .collect()
     }
 
     fn neighbourhood(&self) -> Vec<Self> {
-        (-1..2)
-            .permutations(3)
-            .map(|o| self.add(&(o[0], o[1], o[2])))
+        PtN::from(*self)
+            .neighbourhood()
+            .into_iter()
+            .map(Pt3d::from)
+            .collect()
+    }
+}
+
+//a point in any number of dimensions
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct PtN<const D: usize>(pub [i32; D]);
+
+impl<const D: usize> PtN<D> {
+    pub fn origin() -> Self {
+        Self([0; D])
+    }
+
+    //every offset with components in -1..=1, the first axis changing fastest
+    //(so in 2D, row by row like Pt2d's neighbourhood)
+    fn offsets() -> impl Iterator<Item = Self> {
+        (0..3usize.pow(D as u32)).map(|n| {
+            let mut o = [0; D];
+            let mut rest = n;
+            for c in o.iter_mut() {
+                *c = (rest % 3) as i32 - 1;
+                rest /= 3;
+            }
+            Self(o)
+        })
+    }
+}
+
+impl<const D: usize> Point for PtN<D> {
+    fn add(self, other: &Self) -> Self {
+        Self(std::array::from_fn(|i| self.0[i] + other.0[i]))
+    }
+
+    fn sub(self, other: &Self) -> Self {
+        Self(std::array::from_fn(|i| self.0[i] - other.0[i]))
+    }
+
+    fn mul(self, v: i32) -> Self {
+        Self(self.0.map(|c| c * v))
+    }
+
+    fn mag(self) -> i32 {
+        self.0.iter().map(|c| c.abs()).sum()
+    }
+
+    fn neighbours_card(&self) -> Vec<Self> {
+        (0..D)
+            .flat_map(|i| [-1, 1].map(|d| (i, d)))
+            .map(|(i, d)| {
+                let mut p = *self;
+                p.0[i] += d;
+                p
+            })
+            .collect()
+    }
+
+    fn neighbours_all(&self) -> Vec<Self> {
+        Self::offsets()
+            .filter(|o| *o != Self::origin())
+            .map(|o| self.add(&o))
             .collect()
     }
+
+    fn neighbourhood(&self) -> Vec<Self> {
+        Self::offsets().map(|o| self.add(&o)).collect()
+    }
+}
+
+impl<const D: usize> From<[i32; D]> for PtN<D> {
+    fn from(p: [i32; D]) -> Self {
+        Self(p)
+    }
+}
+
+impl From<Pt2d> for PtN<2> {
+    fn from((x, y): Pt2d) -> Self {
+        Self([x, y])
+    }
+}
+
+impl From<PtN<2>> for Pt2d {
+    fn from(PtN([x, y]): PtN<2>) -> Self {
+        (x, y)
+    }
 }
 
+impl From<Pt3d> for PtN<3> {
+    fn from((x, y, z): Pt3d) -> Self {
+        Self([x, y, z])
+    }
+}
+
+impl From<PtN<3>> for Pt3d {
+    fn from(PtN([x, y, z]): PtN<3>) -> Self {
+        (x, y, z)
+    }
+}
+
+#[derive(Clone)]
 pub struct Grid<K: Point, V> {
     pub grid: HashMap<K, V>,
     pub default: V,
@@ -289,7 +646,7 @@ impl<K: Point + Eq + Hash + Copy, V: PartialEq + Copy> Grid<K, V> {
         Self {
             grid: g,
             default: def,
-            ptr: ptr,
+            ptr,
         }
     }
 
@@ -321,7 +678,7 @@ impl<K: Point + Eq + Hash + Copy, V: PartialEq + Copy> Grid<K, V> {
         found.insert(p);
         let mut search: VecDeque<K> = VecDeque::from([p]);
 
-        while search.len() > 0 {
+        while !search.is_empty() {
             let around = search.pop_front().unwrap();
 
             let ns: HashSet<K> = around
@@ -335,7 +692,7 @@ impl<K: Point + Eq + Hash + Copy, V: PartialEq + Copy> Grid<K, V> {
             search.extend(ns);
         }
 
-        return found;
+        found
     }
 }
 
@@ -344,18 +701,36 @@ impl<V: PartialEq + Copy> Grid<Pt2d, V> {
         let g: HashMap<Pt2d, V> = input
             .lines()
             .enumerate()
-            .map(|(y, l)| {
+            .flat_map(|(y, l)| {
                 l.chars()
                     .enumerate()
                     .map(|(x, c)| ((x as i32, y as i32), p(c)))
                     .collect::<Vec<_>>()
             })
-            .flatten()
             .collect();
 
         Grid::new(g, (0, 0), def)
     }
 
+    //like from, but reports the position of any character the parser rejects
+    pub fn parse(input: &str, def: V, p: fn(char) -> Option<V>) -> Result<Self, SolveError> {
+        let mut g = HashMap::new();
+
+        for (y, l) in input.lines().enumerate() {
+            for (x, c) in l.chars().enumerate() {
+                match p(c) {
+                    Some(v) => g.insert((x as i32, y as i32), v),
+                    None => {
+                        return Err(SolveError::parse(format!("Unexpected character {:?}", c))
+                            .at(y + 1, x + 1))
+                    }
+                };
+            }
+        }
+
+        Ok(Grid::new(g, (0, 0), def))
+    }
+
     pub fn bounds(&self) -> (i32, i32, i32, i32) {
         self.grid.keys().fold((0, 0, 0, 0), |bounds, pt| {
             (
@@ -367,7 +742,7 @@ impl<V: PartialEq + Copy> Grid<Pt2d, V> {
         })
     }
 
-    pub fn print_2d<'a>(&self, pfunc: fn(V) -> char) -> String {
+    pub fn print_2d(&self, pfunc: fn(V) -> char) -> String {
         let mut res = String::from("\n");
         let (min_x, min_y, max_x, max_y) = self.bounds();
 
@@ -379,4 +754,233 @@ impl<V: PartialEq + Copy> Grid<Pt2d, V> {
         }
         res
     }
+
+    //the same values with every point moved, keeping the pointer on the value it was on
+    fn moved(&self, f: impl Fn(Pt2d) -> Pt2d) -> Self {
+        Grid::new(
+            self.grid.iter().map(|(k, v)| (f(*k), *v)).collect(),
+            f(self.ptr),
+            self.default,
+        )
+    }
+
+    //the smallest rectangle holding every point, which unlike bounds needn't include the origin
+    fn extents(&self) -> (i32, i32, i32, i32) {
+        let mut keys = self.grid.keys();
+        let Some(&(x, y)) = keys.next() else {
+            return (0, 0, 0, 0);
+        };
+        keys.fold((x, y, x, y), |(min_x, min_y, max_x, max_y), pt| {
+            (
+                min_x.min(pt.0),
+                min_y.min(pt.1),
+                max_x.max(pt.0),
+                max_y.max(pt.1),
+            )
+        })
+    }
+
+    //the transforms below keep the grid's top-left corner where it is, turning or mirroring within its extents
+    pub fn rotate_90(&self) -> Self {
+        let (min_x, min_y, _, max_y) = self.extents();
+        self.moved(|(x, y)| (min_x + max_y - y, min_y + x - min_x))
+    }
+
+    pub fn rotate_180(&self) -> Self {
+        let (min_x, min_y, max_x, max_y) = self.extents();
+        self.moved(|(x, y)| (min_x + max_x - x, min_y + max_y - y))
+    }
+
+    pub fn rotate_270(&self) -> Self {
+        let (min_x, min_y, max_x, _) = self.extents();
+        self.moved(|(x, y)| (min_x + y - min_y, min_y + max_x - x))
+    }
+
+    //mirror left to right
+    pub fn flip_h(&self) -> Self {
+        let (min_x, _, max_x, _) = self.extents();
+        self.moved(|(x, y)| (min_x + max_x - x, y))
+    }
+
+    //mirror top to bottom
+    pub fn flip_v(&self) -> Self {
+        let (_, min_y, _, max_y) = self.extents();
+        self.moved(|(x, y)| (x, min_y + max_y - y))
+    }
+
+    //swap rows and columns
+    pub fn transpose(&self) -> Self {
+        let (min_x, min_y, _, _) = self.extents();
+        self.moved(|(x, y)| (min_x + y - min_y, min_y + x - min_x))
+    }
+
+    //fold the part beyond the line at the given x or y back over the part before it,
+    //merging each folded value into the one it lands on. points on the line are dropped
+    pub fn fold(&self, axis: Axis, at: i32, merge: fn(V, V) -> V) -> Self {
+        let (keep, over): (Vec<_>, Vec<_>) = self
+            .grid
+            .iter()
+            .filter(|((x, y), _)| match axis {
+                Axis::X => *x != at,
+                Axis::Y => *y != at,
+            })
+            .partition(|((x, y), _)| match axis {
+                Axis::X => *x < at,
+                Axis::Y => *y < at,
+            });
+
+        let mut g = Grid::new(
+            keep.into_iter().map(|(k, v)| (*k, *v)).collect(),
+            self.ptr,
+            self.default,
+        );
+        for ((x, y), v) in over {
+            let k = match axis {
+                Axis::X => (2 * at - x, *y),
+                Axis::Y => (*x, 2 * at - y),
+            };
+            g.grid.insert(k, merge(g.get_def(&k), *v));
+        }
+        g
+    }
+
+    //just the points within the rectangle from min to max, inclusive
+    pub fn crop(&self, (min_x, min_y): Pt2d, (max_x, max_y): Pt2d) -> Self {
+        Grid::new(
+            self.grid
+                .iter()
+                .filter(|((x, y), _)| (min_x..=max_x).contains(x) && (min_y..=max_y).contains(y))
+                .map(|(k, v)| (*k, *v))
+                .collect(),
+            self.ptr,
+            self.default,
+        )
+    }
+
+    //n copies across and m down, each copy's values mapped given its (column, row) of tiles
+    pub fn tile(&self, n: i32, m: i32, f: fn(V, Pt2d) -> V) -> Self {
+        let (min_x, min_y, max_x, max_y) = self.extents();
+        let (w, h) = (max_x - min_x + 1, max_y - min_y + 1);
+
+        let mut g = Grid::new(HashMap::new(), self.ptr, self.default);
+        for (tx, ty) in (0..n).cartesian_product(0..m) {
+            g.grid.extend(
+                self.grid
+                    .iter()
+                    .map(|((x, y), v)| ((x + tx * w, y + ty * h), f(*v, (tx, ty)))),
+            );
+        }
+        g
+    }
+}
+
+//which coordinate a fold line is fixed in: x=n is a vertical line, y=n a horizontal one
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Axis {
+    X,
+    Y,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn check_counts<const D: usize>() {
+        let p = PtN::<D>([5; D]);
+        let card = p.neighbours_card();
+        let all = p.neighbours_all();
+        let hood = p.neighbourhood();
+
+        assert_eq!(card.len(), 2 * D);
+        assert_eq!(all.len(), 3usize.pow(D as u32) - 1);
+        assert_eq!(hood.len(), 3usize.pow(D as u32));
+
+        //no repeats, all adjacent, and only the neighbourhood includes the point itself
+        assert_eq!(all.iter().collect::<HashSet<_>>().len(), all.len());
+        assert_eq!(hood.iter().collect::<HashSet<_>>().len(), hood.len());
+        assert!(hood.iter().all(|n| n.0.iter().all(|c| (4..=6).contains(c))));
+        assert!(card.iter().all(|n| n.sub(&p).mag() == 1));
+        assert!(!all.contains(&p) && hood.contains(&p));
+    }
+
+    #[test]
+    fn neighbour_counts() {
+        check_counts::<1>();
+        check_counts::<2>();
+        check_counts::<3>();
+        check_counts::<4>();
+    }
+
+    #[test]
+    fn tuples_match_ptn() {
+        let p: Pt2d = (3, -2);
+        let as_n = |ns: Vec<PtN<2>>| ns.into_iter().map(Pt2d::from).collect::<HashSet<_>>();
+        let pn = PtN::from(p);
+        assert_eq!(
+            p.neighbours_card().into_iter().collect::<HashSet<_>>(),
+            as_n(pn.neighbours_card())
+        );
+        assert_eq!(
+            p.neighbours_all().into_iter().collect::<HashSet<_>>(),
+            as_n(pn.neighbours_all())
+        );
+        //order matters in the neighbourhood, e.g. for day 20's bit patterns
+        assert_eq!(
+            p.neighbourhood(),
+            pn.neighbourhood()
+                .into_iter()
+                .map(Pt2d::from)
+                .collect::<Vec<_>>()
+        );
+
+        let q: Pt3d = (1, 2, 3);
+        assert_eq!(q.neighbours_card().len(), 6);
+        assert_eq!(q.neighbours_all().len(), 26);
+        assert_eq!(q.neighbourhood().len(), 27);
+        assert!(q.neighbours_all().contains(&(2, 3, 3)));
+        assert!(q.neighbourhood().contains(&q));
+    }
+
+    #[test]
+    fn transforms() {
+        let g = Grid::from(String::from("ab\ncd\nef"), ' ', |c| c);
+        let show = |g: &Grid<Pt2d, char>| g.print_2d(|c| c);
+
+        assert_eq!(show(&g.rotate_90()), "\neca\nfdb\n");
+        assert_eq!(show(&g.rotate_180()), "\nfe\ndc\nba\n");
+        assert_eq!(show(&g.rotate_270()), "\nbdf\nace\n");
+        assert_eq!(show(&g.flip_h()), "\nba\ndc\nfe\n");
+        assert_eq!(show(&g.flip_v()), "\nef\ncd\nab\n");
+        assert_eq!(show(&g.transpose()), "\nace\nbdf\n");
+        assert_eq!(show(&g.crop((1, 1), (1, 2))), "\n  \n d\n f\n");
+        assert_eq!(show(&g.fold(Axis::Y, 1, |a, b| a.max(b))), "\nef\n");
+        assert_eq!(
+            show(&g.tile(2, 1, |c, (tx, _)| if tx == 0 {
+                c
+            } else {
+                c.to_ascii_uppercase()
+            })),
+            "\nabAB\ncdCD\nefEF\n"
+        );
+    }
+
+    //a grid away from the origin stays where it is, at x=-5..-3 and y=10..11
+    #[test]
+    fn offset_transforms() {
+        let g = Grid::from(String::from("abc\ndef"), ' ', |c| c).moved(|(x, y)| (x - 5, y + 10));
+        let extents = |g: &Grid<Pt2d, char>| g.extents();
+        let row =
+            |g: &Grid<Pt2d, char>, y| (-5..=-3).map(|x| g.get_def(&(x, y))).collect::<String>();
+
+        assert_eq!(extents(&g.flip_h()), (-5, 10, -3, 11));
+        assert_eq!(row(&g.flip_h(), 10), "cba");
+        assert_eq!(row(&g.flip_v(), 10), "def");
+        assert_eq!(row(&g.rotate_180(), 11), "cba");
+        assert_eq!(extents(&g.rotate_90()), (-5, 10, -4, 12));
+        assert_eq!(extents(&g.transpose()), (-5, 10, -4, 12));
+        assert_eq!(row(&g.transpose(), 12), "cf ");
+        assert_eq!(extents(&g.rotate_270()), (-5, 10, -4, 12));
+        assert_eq!(row(&g.rotate_270(), 10), "cf ");
+        assert_eq!(extents(&g.tile(2, 2, |c, _| c)), (-5, 10, 0, 13));
+    }
 }
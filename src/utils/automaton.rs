@@ -0,0 +1,314 @@
+use super::pathfinding::Cells;
+use super::{DenseGrid, Grid, Point, Pt2d};
+use std::collections::HashSet;
+use std::hash::Hash;
+use std::marker::PhantomData;
+
+//Traits
+
+//grids an automaton can step: cells can be written anywhere, and everything
+//not stored has the background value
+pub trait Board<K, V>: Cells<K, V> {
+    fn set_cell(&mut self, k: K, v: V);
+    fn set_cells(&mut self, kvs: Vec<(K, V)>) {
+        for (k, v) in kvs {
+            self.set_cell(k, v);
+        }
+    }
+    fn points(&self) -> Vec<K>;
+    fn count(&self) -> usize;
+    //the stored points along with every neighbour of them that isn't stored
+    fn points_and_edges(&self, neighbours: fn(&K) -> Vec<K>) -> Vec<K>
+    where
+        K: Eq + Hash + Copy,
+    {
+        let mut res: HashSet<K> = self.points().into_iter().collect();
+        let edges: Vec<K> = res.iter().flat_map(neighbours).collect();
+        res.extend(edges);
+        res.into_iter().collect()
+    }
+    fn background(&self) -> V;
+    fn set_background(&mut self, v: V);
+}
+
+impl<K: Point + Eq + Hash + Copy, V: PartialEq + Copy> Board<K, V> for Grid<K, V> {
+    fn set_cell(&mut self, k: K, v: V) {
+        self.grid.insert(k, v);
+    }
+
+    fn points(&self) -> Vec<K> {
+        self.grid.keys().cloned().collect()
+    }
+
+    fn count(&self) -> usize {
+        self.grid.len()
+    }
+
+    fn background(&self) -> V {
+        self.default
+    }
+
+    fn set_background(&mut self, v: V) {
+        self.default = v;
+    }
+}
+
+impl<V: PartialEq + Copy> Board<Pt2d, V> for DenseGrid<V> {
+    fn set_cell(&mut self, k: Pt2d, v: V) {
+        self.set(k, v);
+    }
+
+    fn set_cells(&mut self, kvs: Vec<(Pt2d, V)>) {
+        self.inserts(kvs);
+    }
+
+    fn points(&self) -> Vec<Pt2d> {
+        self.keys().collect()
+    }
+
+    fn count(&self) -> usize {
+        self.len()
+    }
+
+    //the rectangle with a border around it, which holds every neighbour a Point can have
+    fn points_and_edges(&self, _: fn(&Pt2d) -> Vec<Pt2d>) -> Vec<Pt2d> {
+        let (min_x, min_y, max_x, max_y) = self.bounds();
+        (min_y - 1..max_y + 2)
+            .flat_map(|y| (min_x - 1..max_x + 2).map(move |x| (x, y)))
+            .collect()
+    }
+
+    fn background(&self) -> V {
+        self.default
+    }
+
+    fn set_background(&mut self, v: V) {
+        self.default = v;
+    }
+}
+
+//Structs
+
+//a grid stepped by a rule over each cell's neighbours. a bounded automaton only has the cells of its grid,
+//while an infinite one applies the rule to the background too, storing any cell that ends up different from it.
+//neighbourhoods must be symmetric (as all of Point's are), since a cell is only revisited once something it
+//sees has changed
+pub struct CellularAutomaton<K, V, G> {
+    pub grid: G,
+    pub neighbours: fn(&K) -> Vec<K>,
+    pub infinite: bool,
+    pub generation: usize,
+    //cells changed since the last step, or none if anything could have
+    changed: Option<HashSet<K>>,
+    values: PhantomData<V>,
+}
+
+impl<K, V, G> CellularAutomaton<K, V, G>
+where
+    K: Point + Eq + Hash + Copy,
+    V: PartialEq + Copy,
+    G: Board<K, V>,
+{
+    pub fn bounded(grid: G, neighbours: fn(&K) -> Vec<K>) -> Self {
+        Self {
+            grid,
+            neighbours,
+            infinite: false,
+            generation: 0,
+            changed: None,
+            values: PhantomData,
+        }
+    }
+
+    pub fn infinite(grid: G, neighbours: fn(&K) -> Vec<K>) -> Self {
+        Self {
+            infinite: true,
+            ..Self::bounded(grid, neighbours)
+        }
+    }
+
+    //revisit every cell next step, e.g. after changing the grid directly
+    pub fn revisit_all(&mut self) {
+        self.changed = None;
+    }
+
+    //a cell's value, with cells off the board being the background when it's infinite
+    fn value(&self, k: &K) -> Option<V> {
+        match self.grid.cell(k) {
+            None if self.infinite => Some(self.grid.background()),
+            v => v,
+        }
+    }
+
+    //the cells whose neighbourhood changed last step
+    fn candidates(&self) -> Vec<K> {
+        match (&self.changed, self.infinite) {
+            (Some(c), _) => {
+                let mut res = c.clone();
+                res.extend(c.iter().flat_map(|k| (self.neighbours)(k)));
+                res.into_iter()
+                    .filter(|k| self.value(k).is_some())
+                    .collect()
+            }
+            //on an infinite board, the background at the edges may change too
+            (None, true) => self.grid.points_and_edges(self.neighbours),
+            (None, false) => self.grid.points(),
+        }
+    }
+
+    //update every cell at once from its value and its neighbours' values (in the order neighbours gives them),
+    //returning how many cells changed
+    pub fn step(&mut self, rule: impl Fn(V, &[V]) -> V) -> usize {
+        let bg = self.grid.background();
+        let candidates = self.candidates();
+
+        //what the rest of the infinite board becomes
+        let new_bg = match candidates.first() {
+            Some(k) if self.infinite => rule(bg, &vec![bg; (self.neighbours)(k).len()]),
+            _ => bg,
+        };
+
+        let mut updates: Vec<(K, V)> = vec![];
+        let mut ns: Vec<V> = vec![];
+        for k in candidates {
+            let Some(v) = self.value(&k) else {
+                continue;
+            };
+            ns.clear();
+            ns.extend((self.neighbours)(&k).iter().filter_map(|n| self.value(n)));
+
+            //cells off the board are stored once they differ from the background
+            let nv = rule(v, &ns);
+            if nv != v || (self.grid.cell(&k).is_none() && nv != new_bg) {
+                updates.push((k, nv));
+            }
+        }
+
+        //once most cells have changed, it's quicker to just revisit them all
+        let changed = updates.len();
+        self.changed = match new_bg == bg && changed < self.grid.count() / 2 {
+            true => Some(updates.iter().map(|(k, _)| *k).collect()),
+            false => None,
+        };
+        self.grid.set_cells(updates);
+        self.grid.set_background(new_bg);
+        self.generation += 1;
+
+        changed
+    }
+
+    //let cells set each other off: every cell that fires (once at most) spreads to its neighbours,
+    //which may fire in turn, and once it's over each fired cell settles. returns the cells that fired
+    pub fn cascade(
+        &mut self,
+        fires: impl Fn(V) -> bool,
+        spread: impl Fn(V) -> V,
+        settle: impl Fn(V) -> V,
+    ) -> HashSet<K> {
+        let mut fired = HashSet::new();
+        let mut touched = HashSet::new();
+        let mut search: Vec<K> = self
+            .grid
+            .points()
+            .into_iter()
+            .filter(|k| self.grid.cell(k).is_some_and(&fires))
+            .collect();
+
+        while let Some(k) = search.pop() {
+            if !fired.insert(k) {
+                continue;
+            }
+            for n in (self.neighbours)(&k) {
+                let Some(v) = self.value(&n).filter(|_| !fired.contains(&n)) else {
+                    continue;
+                };
+                let nv = spread(v);
+                self.grid.set_cell(n, nv);
+                touched.insert(n);
+                if fires(nv) {
+                    search.push(n);
+                }
+            }
+        }
+
+        for k in fired.iter() {
+            let v = self.grid.cell(k).unwrap();
+            self.grid.set_cell(*k, settle(v));
+        }
+
+        if let Some(c) = &mut self.changed {
+            c.extend(touched);
+            c.extend(fired.iter().cloned());
+        }
+        fired
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::PtN;
+    use std::collections::HashMap;
+
+    //life in more dimensions: active cells stay active with 2 or 3 active neighbours, and inactive ones
+    //become active with exactly 3
+    fn cubes<const D: usize>(start: &str, cycles: usize) -> usize {
+        let active: HashMap<PtN<D>, bool> = start
+            .lines()
+            .enumerate()
+            .flat_map(|(y, l)| {
+                l.chars().enumerate().map(move |(x, c)| {
+                    let mut p = [0; D];
+                    (p[0], p[1]) = (x as i32, y as i32);
+                    (PtN(p), c == '#')
+                })
+            })
+            .collect();
+
+        let mut life = CellularAutomaton::infinite(
+            Grid::new(active, PtN::origin(), false),
+            Point::neighbours_all,
+        );
+        for _ in 0..cycles {
+            life.step(|v, ns| {
+                let n = ns.iter().filter(|n| **n).count();
+                n == 3 || (v && n == 2)
+            });
+        }
+        life.grid.grid.values().filter(|v| **v).count()
+    }
+
+    #[test]
+    fn conway_cubes() {
+        let start = ".#.\n..#\n###";
+        assert_eq!(cubes::<3>(start, 6), 112);
+        assert_eq!(cubes::<4>(start, 6), 848);
+    }
+
+    #[test]
+    fn glider() {
+        let g = Grid::from(String::from(".#.\n..#\n###"), false, |c| c == '#');
+        let mut life = CellularAutomaton::infinite(g, Point::neighbours_all);
+        for _ in 0..8 {
+            life.step(|v, ns| {
+                let n = ns.iter().filter(|n| **n).count();
+                n == 3 || (v && n == 2)
+            });
+        }
+
+        //two full periods move it two down and two across, only revisiting the cells around it
+        let alive: HashSet<Pt2d> = life
+            .grid
+            .grid
+            .iter()
+            .filter(|(_, v)| **v)
+            .map(|(k, _)| *k)
+            .collect();
+        assert_eq!(
+            alive,
+            HashSet::from([(3, 2), (4, 3), (2, 4), (3, 4), (4, 4)])
+        );
+        assert_eq!(life.generation, 8);
+        assert!(life.changed.as_ref().is_some_and(|c| c.len() < 10));
+    }
+}
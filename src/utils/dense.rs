@@ -0,0 +1,341 @@
+use super::{Grid, Point, Pt2d, SolveError};
+use itertools::Itertools;
+use std::collections::{HashSet, VecDeque};
+
+//Structs
+
+//a grid stored as rows in one Vec, for inputs that fill a rectangle. the cell at origin is stored
+//first, anything outside the rectangle reads as the default, and writing outside it grows the rectangle.
+//flood_find never leaves the rectangle, unlike Grid's, which spreads outside when limiter(default) is true
+#[derive(Clone)]
+pub struct DenseGrid<V> {
+    pub cells: Vec<V>,
+    pub origin: Pt2d,
+    pub width: i32,
+    pub height: i32,
+    pub default: V,
+    pub ptr: Pt2d,
+}
+
+impl<V: PartialEq + Copy> DenseGrid<V> {
+    //a width x height rectangle of the default, starting at origin
+    pub fn new(origin: Pt2d, (width, height): Pt2d, def: V) -> Self {
+        Self {
+            cells: vec![def; (width.max(0) * height.max(0)) as usize],
+            origin,
+            width: width.max(0),
+            height: height.max(0),
+            default: def,
+            ptr: origin,
+        }
+    }
+
+    pub fn from(input: String, def: V, p: fn(char) -> V) -> Self {
+        let width = input.lines().map(|l| l.chars().count()).max().unwrap_or(0);
+        let mut g = Self::new((0, 0), (width as i32, input.lines().count() as i32), def);
+
+        for (y, l) in input.lines().enumerate() {
+            for (x, c) in l.chars().enumerate() {
+                g.set((x as i32, y as i32), p(c));
+            }
+        }
+
+        g
+    }
+
+    //like from, but reports the position of any character the parser rejects
+    pub fn parse(input: &str, def: V, p: fn(char) -> Option<V>) -> Result<Self, SolveError> {
+        let width = input.lines().map(|l| l.chars().count()).max().unwrap_or(0);
+        let mut g = Self::new((0, 0), (width as i32, input.lines().count() as i32), def);
+
+        for (y, l) in input.lines().enumerate() {
+            for (x, c) in l.chars().enumerate() {
+                match p(c) {
+                    Some(v) => g.set((x as i32, y as i32), v),
+                    None => {
+                        return Err(SolveError::parse(format!("Unexpected character {:?}", c))
+                            .at(y + 1, x + 1))
+                    }
+                }
+            }
+        }
+
+        Ok(g)
+    }
+
+    //the index of a point in cells, if it's inside the rectangle
+    pub fn index(&self, (x, y): &Pt2d) -> Option<usize> {
+        let (dx, dy) = (x - self.origin.0, y - self.origin.1);
+        if dx < 0 || dy < 0 || dx >= self.width || dy >= self.height {
+            return None;
+        }
+        Some((dy * self.width + dx) as usize)
+    }
+
+    pub fn point(&self, i: usize) -> Pt2d {
+        let i = i as i32;
+        (
+            self.origin.0 + i % self.width,
+            self.origin.1 + i / self.width,
+        )
+    }
+
+    pub fn contains(&self, p: &Pt2d) -> bool {
+        self.index(p).is_some()
+    }
+
+    pub fn get(&self, p: &Pt2d) -> Option<V> {
+        self.index(p).map(|i| self.cells[i])
+    }
+
+    //get value or default
+    pub fn get_def(&self, p: &Pt2d) -> V {
+        self.get(p).unwrap_or(self.default)
+    }
+
+    //set a value, growing the rectangle to reach it if needed
+    pub fn set(&mut self, p: Pt2d, v: V) {
+        if !self.contains(&p) {
+            self.grow_to(p);
+        }
+        let i = self.index(&p).unwrap();
+        self.cells[i] = v;
+    }
+
+    //grow the rectangle just enough to include p, filling new cells with the default
+    pub fn grow_to(&mut self, p: Pt2d) {
+        self.grow_to_cover(p, p);
+    }
+
+    //grow the rectangle just enough to include the one from lo to hi, copying the grid once
+    fn grow_to_cover(&mut self, lo: Pt2d, hi: Pt2d) {
+        let (min_x, min_y, max_x, max_y) = match self.cells.is_empty() {
+            true => (lo.0, lo.1, hi.0, hi.1),
+            false => {
+                let (a, b, c, d) = self.bounds();
+                (a.min(lo.0), b.min(lo.1), c.max(hi.0), d.max(hi.1))
+            }
+        };
+
+        let mut grown = Self::new(
+            (min_x, min_y),
+            (max_x - min_x + 1, max_y - min_y + 1),
+            self.default,
+        );
+        grown.ptr = self.ptr;
+        for (q, v) in self.iter() {
+            let i = grown.index(&q).unwrap();
+            grown.cells[i] = v;
+        }
+
+        *self = grown;
+    }
+
+    pub fn update(&mut self, k: Pt2d, u: fn(V) -> V) {
+        self.set(k, u(self.get_def(&k)));
+    }
+
+    pub fn updates(&mut self, ks: Vec<Pt2d>, u: fn(V) -> V) {
+        for k in ks {
+            self.update(k, u)
+        }
+    }
+
+    pub fn inserts(&mut self, kvs: Vec<(Pt2d, V)>) {
+        //grow once to fit everything, rather than once per point outside
+        let outside: Vec<Pt2d> = kvs
+            .iter()
+            .map(|(k, _)| *k)
+            .filter(|k| !self.contains(k))
+            .collect();
+        if let Some(first) = outside.first() {
+            let (min_x, min_y, max_x, max_y) = outside.iter().fold(
+                (first.0, first.1, first.0, first.1),
+                |(a, b, c, d), (x, y)| (a.min(*x), b.min(*y), c.max(*x), d.max(*y)),
+            );
+            self.grow_to_cover((min_x, min_y), (max_x, max_y));
+        }
+        for (k, v) in kvs {
+            self.set(k, v);
+        }
+    }
+
+    //every point in the rectangle with its value, row by row
+    pub fn iter(&self) -> impl Iterator<Item = (Pt2d, V)> + '_ {
+        self.cells
+            .iter()
+            .enumerate()
+            .map(|(i, v)| (self.point(i), *v))
+    }
+
+    pub fn keys(&self) -> impl Iterator<Item = Pt2d> + '_ {
+        (0..self.cells.len()).map(|i| self.point(i))
+    }
+
+    pub fn values(&self) -> impl Iterator<Item = &V> {
+        self.cells.iter()
+    }
+
+    pub fn len(&self) -> usize {
+        self.cells.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.cells.is_empty()
+    }
+
+    //finds all points reachable from p in grid under some criteria
+    pub fn flood_find(&self, p: Pt2d, limiter: fn(V) -> bool) -> HashSet<Pt2d> {
+        let mut found = vec![false; self.cells.len()];
+        let mut res = HashSet::from([p]);
+        let mut search: VecDeque<Pt2d> = VecDeque::from([p]);
+        if let Some(i) = self.index(&p) {
+            found[i] = true;
+        }
+
+        while let Some(around) = search.pop_front() {
+            for q in around.neighbours_card() {
+                //points outside the rectangle are all the default, so only the inside is searched
+                let Some(i) = self.index(&q) else {
+                    continue;
+                };
+                if !found[i] && limiter(self.cells[i]) {
+                    found[i] = true;
+                    res.insert(q);
+                    search.push_back(q);
+                }
+            }
+        }
+
+        res
+    }
+
+    //min x, min y, max x, max y of the rectangle
+    pub fn bounds(&self) -> (i32, i32, i32, i32) {
+        (
+            self.origin.0,
+            self.origin.1,
+            self.origin.0 + self.width - 1,
+            self.origin.1 + self.height - 1,
+        )
+    }
+
+    //n copies across and m down, each copy's values mapped given its (column, row) of tiles
+    pub fn tile(&self, n: i32, m: i32, f: fn(V, Pt2d) -> V) -> Self {
+        let mut g = Self::new(self.origin, (self.width * n, self.height * m), self.default);
+        g.ptr = self.ptr;
+        for (tx, ty) in (0..n).cartesian_product(0..m) {
+            for ((x, y), v) in self.iter() {
+                g.set((x + tx * self.width, y + ty * self.height), f(v, (tx, ty)));
+            }
+        }
+        g
+    }
+
+    //the rectangle covering every point of a sparse grid
+    pub fn from_sparse(g: &Grid<Pt2d, V>) -> Self {
+        let mut d = match g.grid.keys().next() {
+            None => Self::new((0, 0), (0, 0), g.default),
+            Some(first) => {
+                let (min_x, min_y, max_x, max_y) = g.grid.keys().fold(
+                    (first.0, first.1, first.0, first.1),
+                    |(a, b, c, d), (x, y)| (a.min(*x), b.min(*y), c.max(*x), d.max(*y)),
+                );
+                Self::new(
+                    (min_x, min_y),
+                    (max_x - min_x + 1, max_y - min_y + 1),
+                    g.default,
+                )
+            }
+        };
+        for (k, v) in g.grid.iter() {
+            d.set(*k, *v);
+        }
+        d.ptr = g.ptr;
+        d
+    }
+
+    pub fn print_2d(&self, pfunc: fn(V) -> char) -> String {
+        let mut res = String::from("\n");
+
+        for row in self.cells.chunks(self.width.max(1) as usize) {
+            res.extend(row.iter().map(|v| pfunc(*v)));
+            res.push('\n')
+        }
+        res
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+
+    fn show(g: &DenseGrid<char>) -> String {
+        g.print_2d(|c| c)
+    }
+
+    #[test]
+    fn grows() {
+        let mut g = DenseGrid::from(String::from("ab\ncd"), '.', |c| c);
+        g.set((-1, 0), 'x');
+        assert_eq!((g.origin, g.width, g.height), ((-1, 0), 3, 2));
+        g.grow_to((0, -2));
+        assert_eq!(g.bounds(), (-1, -2, 1, 1));
+        assert_eq!(show(&g), "\n...\n...\nxab\n.cd\n");
+        assert_eq!((g.get(&(1, 1)), g.get_def(&(5, 5))), (Some('d'), '.'));
+        assert_eq!(g.ptr, (0, 0));
+    }
+
+    #[test]
+    fn inserts_once() {
+        let mut g = DenseGrid::from(String::from("ab\ncd"), '.', |c| c);
+        g.inserts(vec![((-2, 1), 'x'), ((0, 0), 'A'), ((2, -1), 'y')]);
+
+        //one new rectangle covers the old one and every point outside it, and each is then set in place
+        assert_eq!(g.bounds(), (-2, -1, 2, 1));
+        assert_eq!(show(&g), "\n....y\n..Ab.\nx.cd.\n");
+        assert_eq!(g.len(), 15);
+
+        let mut empty = DenseGrid::new((0, 0), (0, 0), '.');
+        empty.inserts(vec![((3, 1), 'a'), ((1, 2), 'b')]);
+        assert_eq!(empty.bounds(), (1, 1, 3, 2));
+        assert_eq!(show(&empty), "\n..a\nb..\n");
+    }
+
+    #[test]
+    fn flood_stays_inside() {
+        let g = DenseGrid::from(String::from("..#\n.##\n#.."), false, |c| c == '.');
+        assert_eq!(
+            g.flood_find((0, 0), |v| v),
+            HashSet::from([(0, 0), (1, 0), (0, 1)])
+        );
+
+        //outside the rectangle only the start is found, even though the default passes
+        let open = DenseGrid::from(String::from("##"), true, |c| c == '.');
+        assert_eq!(open.flood_find((5, 5), |v| v), HashSet::from([(5, 5)]));
+    }
+
+    #[test]
+    fn sparse() {
+        let s = Grid::new(HashMap::from([((-1, 2), 'a'), ((1, 3), 'b')]), (1, 3), '.');
+        let d = DenseGrid::from_sparse(&s);
+        assert_eq!(d.bounds(), (-1, 2, 1, 3));
+        assert_eq!(show(&d), "\na..\n..b\n");
+        assert_eq!(d.ptr, (1, 3));
+        assert_eq!(
+            DenseGrid::from_sparse(&Grid::new(HashMap::new(), (0, 0), '.')).len(),
+            0
+        );
+    }
+
+    #[test]
+    fn prints() {
+        let g = DenseGrid::from(String::from("#.\n.#\n##"), false, |c| c == '#');
+        assert_eq!(g.print_2d(|b| if b { '#' } else { '.' }), "\n#.\n.#\n##\n");
+        assert_eq!(
+            DenseGrid::new((0, 0), (0, 0), false).print_2d(|_| '#'),
+            "\n"
+        );
+    }
+}
@@ -0,0 +1,210 @@
+//Types
+
+pub type Rect = Cuboid<2>;
+pub type Cube = Cuboid<3>;
+
+//Structs
+
+//an axis-aligned box of integer points in D dimensions, including the points on its faces
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Cuboid<const D: usize> {
+    pub min: [i64; D],
+    pub max: [i64; D],
+}
+
+impl<const D: usize> Cuboid<D> {
+    //the box between any two opposite corners
+    pub fn new(a: [i64; D], b: [i64; D]) -> Self {
+        Self {
+            min: std::array::from_fn(|i| a[i].min(b[i])),
+            max: std::array::from_fn(|i| a[i].max(b[i])),
+        }
+    }
+
+    //how many points it holds
+    pub fn volume(&self) -> i64 {
+        (0..D).map(|i| self.max[i] - self.min[i] + 1).product()
+    }
+
+    pub fn contains(&self, p: &[i64; D]) -> bool {
+        (0..D).all(|i| (self.min[i]..=self.max[i]).contains(&p[i]))
+    }
+
+    pub fn intersects(&self, oth: &Self) -> bool {
+        (0..D).all(|i| self.min[i] <= oth.max[i] && oth.min[i] <= self.max[i])
+    }
+
+    pub fn intersection(&self, oth: &Self) -> Option<Self> {
+        self.intersects(oth).then(|| Self {
+            min: std::array::from_fn(|i| self.min[i].max(oth.min[i])),
+            max: std::array::from_fn(|i| self.max[i].min(oth.max[i])),
+        })
+    }
+
+    //non-overlapping boxes covering what's left of self once oth is taken out of it,
+    //slicing off a slab on either side of oth along each axis in turn
+    pub fn minus(&self, oth: &Self) -> Vec<Self> {
+        let Some(cut) = self.intersection(oth) else {
+            return vec![*self];
+        };
+
+        let mut res = vec![];
+        let mut rest = *self;
+        for i in 0..D {
+            if rest.min[i] < cut.min[i] {
+                let mut slab = rest;
+                slab.max[i] = cut.min[i] - 1;
+                res.push(slab);
+                rest.min[i] = cut.min[i];
+            }
+            if rest.max[i] > cut.max[i] {
+                let mut slab = rest;
+                slab.min[i] = cut.max[i] + 1;
+                res.push(slab);
+                rest.max[i] = cut.max[i];
+            }
+        }
+
+        res
+    }
+}
+
+//a region made of non-overlapping boxes
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct Cuboids<const D: usize> {
+    pub parts: Vec<Cuboid<D>>,
+}
+
+impl<const D: usize> Cuboids<D> {
+    pub fn new() -> Self {
+        Self { parts: vec![] }
+    }
+
+    //the region covered by any of the boxes, which may overlap
+    pub fn from(cs: &[Cuboid<D>]) -> Self {
+        let mut res = Self::new();
+        for c in cs {
+            res.add(c);
+        }
+        res
+    }
+
+    pub fn add(&mut self, c: &Cuboid<D>) {
+        self.remove(c);
+        self.parts.push(*c);
+    }
+
+    pub fn remove(&mut self, c: &Cuboid<D>) {
+        let (hit, mut parts): (Vec<Cuboid<D>>, Vec<Cuboid<D>>) =
+            self.parts.iter().partition(|p| p.intersects(c));
+        parts.extend(hit.iter().flat_map(|p| p.minus(c)));
+        self.parts = parts;
+    }
+
+    pub fn volume(&self) -> i64 {
+        self.parts.iter().map(|p| p.volume()).sum()
+    }
+
+    pub fn contains(&self, p: &[i64; D]) -> bool {
+        self.parts.iter().any(|c| c.contains(p))
+    }
+
+    //just the part of the region within the box
+    pub fn clip(&self, region: &Cuboid<D>) -> Self {
+        Self {
+            parts: self
+                .parts
+                .iter()
+                .filter_map(|p| p.intersection(region))
+                .collect(),
+        }
+    }
+
+    pub fn union(&self, oth: &Self) -> Self {
+        let mut res = self.difference(oth);
+        res.parts.extend(oth.parts.iter().cloned());
+        res
+    }
+
+    //parts of the two regions are each disjoint, so their overlaps are too
+    pub fn intersection(&self, oth: &Self) -> Self {
+        Self {
+            parts: oth.parts.iter().flat_map(|o| self.clip(o).parts).collect(),
+        }
+    }
+
+    pub fn difference(&self, oth: &Self) -> Self {
+        let mut res = self.clone();
+        for o in oth.parts.iter() {
+            res.remove(o);
+        }
+        res
+    }
+
+    pub fn symmetric_difference(&self, oth: &Self) -> Self {
+        let mut res = self.difference(oth);
+        res.parts.extend(oth.difference(self).parts);
+        res
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn boxes() {
+        let a = Cube::new([10, 10, 10], [12, 12, 12]);
+        let b = Cube::new([13, 13, 13], [11, 11, 11]);
+        assert_eq!(b.min, [11, 11, 11]);
+        assert_eq!(a.volume(), 27);
+        assert_eq!(
+            a.intersection(&b),
+            Some(Cube::new([11, 11, 11], [12, 12, 12]))
+        );
+        assert_eq!(a.intersection(&Cube::new([13, 0, 0], [20, 20, 20])), None);
+
+        let left = a.minus(&b);
+        assert_eq!(left.iter().map(|c| c.volume()).sum::<i64>(), 27 - 8);
+        assert!(left.iter().all(|c| !c.intersects(&b)));
+        assert_eq!(a.minus(&a), vec![]);
+    }
+
+    //overlapping fabric claims: #1 @ 1,3: 4x4, #2 @ 3,1: 4x4, #3 @ 5,5: 2x2
+    #[test]
+    fn claims() {
+        let claims = [
+            Rect::new([1, 3], [4, 6]),
+            Rect::new([3, 1], [6, 4]),
+            Rect::new([5, 5], [6, 6]),
+        ];
+        let overlaps = Cuboids::from(
+            &claims
+                .iter()
+                .enumerate()
+                .flat_map(|(i, a)| claims[i + 1..].iter().filter_map(|b| a.intersection(b)))
+                .collect::<Vec<_>>(),
+        );
+        assert_eq!(overlaps.volume(), 4);
+        assert!(overlaps.contains(&[3, 3]) && !overlaps.contains(&[5, 5]));
+        assert_eq!(Cuboids::from(&claims).volume(), 16 + 16 - 4 + 4);
+    }
+
+    #[test]
+    fn set_algebra() {
+        let a = Cuboids::from(&[Rect::new([0, 0], [9, 9]), Rect::new([5, 5], [14, 14])]);
+        let b = Cuboids::from(&[Rect::new([8, -3], [20, 6])]);
+        let (va, vb) = (a.volume(), b.volume());
+        assert_eq!((va, vb), (175, 130));
+
+        let both = a.intersection(&b).volume();
+        assert_eq!(both, 14 + 14 - 4);
+        assert_eq!(a.union(&b).volume(), va + vb - both);
+        assert_eq!(a.difference(&b).volume(), va - both);
+        assert_eq!(a.symmetric_difference(&b).volume(), va + vb - 2 * both);
+
+        let clipped = a.clip(&Rect::new([-5, -5], [4, 4]));
+        assert_eq!(clipped.volume(), 25);
+        assert!(a.contains(&[14, 14]) && !a.contains(&[14, 0]) && !clipped.contains(&[5, 5]));
+    }
+}
@@ -0,0 +1,174 @@
+use crate::utils::{Grid, Pt2d};
+use std::fmt::{self, Display, Formatter};
+
+//Constants
+
+//the block font the puzzles draw answers in: 4 wide, 6 tall, with a blank column between letters
+const WIDTH: i32 = 4;
+const HEIGHT: i32 = 6;
+const FONT: [(char, [&str; 6]); 17] = [
+    ('A', [".##.", "#..#", "#..#", "####", "#..#", "#..#"]),
+    ('B', ["###.", "#..#", "###.", "#..#", "#..#", "###."]),
+    ('C', [".##.", "#..#", "#...", "#...", "#..#", ".##."]),
+    ('E', ["####", "#...", "###.", "#...", "#...", "####"]),
+    ('F', ["####", "#...", "###.", "#...", "#...", "#..."]),
+    ('G', [".##.", "#..#", "#...", "#.##", "#..#", ".###"]),
+    ('H', ["#..#", "#..#", "####", "#..#", "#..#", "#..#"]),
+    ('I', [".###", "..#.", "..#.", "..#.", "..#.", ".###"]),
+    ('J', ["..##", "...#", "...#", "...#", "#..#", ".##."]),
+    ('K', ["#..#", "#.#.", "##..", "#.#.", "#.#.", "#..#"]),
+    ('L', ["#...", "#...", "#...", "#...", "#...", "####"]),
+    ('O', [".##.", "#..#", "#..#", "#..#", "#..#", ".##."]),
+    ('P', ["###.", "#..#", "#..#", "###.", "#...", "#..."]),
+    ('R', ["###.", "#..#", "#..#", "###.", "#.#.", "#..#"]),
+    ('S', [".###", "#...", "#...", ".##.", "...#", "###."]),
+    ('U', ["#..#", "#..#", "#..#", "#..#", "#..#", ".##."]),
+    ('Z', ["####", "...#", "..#.", ".#..", "#...", "####"]),
+];
+
+//Structs
+
+//a glyph that isn't in the font: which letter it was, the column it starts at, and what it looked like
+#[derive(Debug, Clone, PartialEq)]
+pub struct Unrecognised {
+    pub index: usize,
+    pub x: i32,
+    pub glyph: String,
+}
+
+impl Display for Unrecognised {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "Unrecognised letter {} at column {}:\n{}",
+            self.index + 1,
+            self.x,
+            self.glyph
+        )
+    }
+}
+
+//Functions
+
+//read the letters drawn by the lit points of a grid, e.g. "EFJKZLBL". letters start from the top left of
+//the stored points, or a column before it when the first letter is an I (the only one with a blank first column)
+//and only lit points are stored
+pub fn read(g: &Grid<Pt2d, bool>) -> Result<String, Unrecognised> {
+    let lit: Vec<Pt2d> = g
+        .grid
+        .iter()
+        .filter(|(_, v)| **v)
+        .map(|(k, _)| *k)
+        .collect();
+    let (Some(max_x), Some(min_x), Some(min_y)) = (
+        lit.iter().map(|p| p.0).max(),
+        g.grid.keys().map(|p| p.0).min(),
+        g.grid.keys().map(|p| p.1).min(),
+    ) else {
+        return Ok(String::new());
+    };
+
+    read_from(g, &lit, (min_x, min_y), max_x)
+        .or_else(|e| read_from(g, &lit, (min_x - 1, min_y), max_x).map_err(|_| e))
+}
+
+//read the letters whose cells start at the given top left, up to the last lit column
+fn read_from(
+    g: &Grid<Pt2d, bool>,
+    lit: &[Pt2d],
+    (min_x, min_y): Pt2d,
+    max_x: i32,
+) -> Result<String, Unrecognised> {
+    let letters = (max_x - min_x) / (WIDTH + 1) + 1;
+
+    (0..letters)
+        .map(|i| {
+            let x = min_x + i * (WIDTH + 1);
+            let glyph: Vec<String> = (0..HEIGHT)
+                .map(|dy| {
+                    (0..WIDTH)
+                        .map(|dx| {
+                            if g.get_def(&(x + dx, min_y + dy)) {
+                                '#'
+                            } else {
+                                '.'
+                            }
+                        })
+                        .collect()
+                })
+                .collect();
+
+            //anything lit outside the letter's cell means it isn't in this font either
+            let overflow = lit
+                .iter()
+                .any(|(px, py)| (x..x + WIDTH).contains(px) && *py >= min_y + HEIGHT);
+
+            FONT.iter()
+                .find(|(_, f)| glyph == *f && !overflow)
+                .map(|(c, _)| *c)
+                .ok_or_else(|| Unrecognised {
+                    index: i as usize,
+                    x,
+                    glyph: glyph.join("\n"),
+                })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    //draw a message in the font, starting at (x, y)
+    fn draw(msg: &str, x: i32, y: i32) -> Grid<Pt2d, bool> {
+        let mut lit = vec![];
+        for (i, c) in msg.chars().enumerate() {
+            let rows = FONT.iter().find(|(f, _)| *f == c).map(|(_, r)| r).unwrap();
+            for (dy, row) in rows.iter().enumerate() {
+                for (dx, p) in row.chars().enumerate() {
+                    if p == '#' {
+                        lit.push((x + i as i32 * (WIDTH + 1) + dx as i32, y + dy as i32));
+                    }
+                }
+            }
+        }
+        Grid::new(lit.into_iter().map(|p| (p, true)).collect(), (0, 0), false)
+    }
+
+    #[test]
+    fn reads_back() {
+        assert_eq!(read(&draw("ICEBLOCK", 0, 0)), Ok(String::from("ICEBLOCK")));
+        assert_eq!(
+            read(&draw("HJUFPRSZAG", 0, 0)),
+            Ok(String::from("HJUFPRSZAG"))
+        );
+        assert_eq!(read(&draw("", 0, 0)), Ok(String::new()));
+    }
+
+    //away from the origin, with and without the blank column before an I stored
+    #[test]
+    fn reads_offset() {
+        assert_eq!(read(&draw("LIKE", 3, -2)), Ok(String::from("LIKE")));
+        assert_eq!(read(&draw("IF", 7, 4)), Ok(String::from("IF")));
+
+        let mut g = draw("IF", -9, 4);
+        g.grid.insert((-9, 9), false);
+        assert_eq!(read(&g), Ok(String::from("IF")));
+    }
+
+    #[test]
+    fn reports_bad_glyph() {
+        let mut g = draw("LIL", 0, 0);
+        //take the top off the I
+        for x in 6..9 {
+            g.grid.insert((x, 0), false);
+        }
+
+        let err = read(&g).unwrap_err();
+        assert_eq!((err.index, err.x), (1, 5));
+        assert_eq!(err.glyph, "....\n..#.\n..#.\n..#.\n..#.\n.###");
+        assert!(err
+            .to_string()
+            .starts_with("Unrecognised letter 2 at column 5"));
+    }
+}
@@ -0,0 +1,307 @@
+use super::{DenseGrid, Grid, Pt2d, SolveError};
+use regex::{Captures, Regex};
+use std::fmt::Display;
+use std::str::FromStr;
+
+//Structs
+
+//a block of the input between blank lines, remembering the (0-indexed) line it starts on
+//so errors found in it can point into the whole input
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Section<'a> {
+    pub text: &'a str,
+    pub start: usize,
+}
+
+impl<'a> Section<'a> {
+    //the whole input as one section
+    pub fn whole(text: &'a str) -> Self {
+        Self { text, start: 0 }
+    }
+
+    //each line along with its 0-indexed line in the whole input
+    pub fn lines(&self) -> impl Iterator<Item = (usize, &'a str)> + 'a {
+        let start = self.start;
+        self.text
+            .lines()
+            .enumerate()
+            .map(move |(i, l)| (start + i, l))
+    }
+
+    //move an error found in just this section's text to where it is in the whole input
+    pub fn lift(&self, e: SolveError) -> SolveError {
+        e.offset_lines(self.start)
+    }
+}
+
+//a regex whole lines must match, and what it expects for when they don't
+pub struct Pattern {
+    re: Regex,
+    expected: &'static str,
+}
+
+impl Pattern {
+    pub fn new(re: &str, expected: &'static str) -> Self {
+        Self {
+            re: Regex::new(&format!("^(?:{})$", re)).unwrap(),
+            expected,
+        }
+    }
+
+    //match the given (0-indexed) line
+    pub fn fields<'a>(&self, l: &'a str, line: usize) -> Result<Fields<'a>, SolveError> {
+        match self.re.captures(l) {
+            Some(caps) => Ok(Fields { caps, line }),
+            None => Err(
+                SolveError::parse(format!("Expected `{}`, found `{}`", self.expected, l))
+                    .at(line + 1, 1),
+            ),
+        }
+    }
+
+    //match and convert each line of a section
+    pub fn parse_lines<T>(
+        &self,
+        s: &Section,
+        mut f: impl FnMut(&Fields) -> Result<T, SolveError>,
+    ) -> Result<Vec<T>, SolveError> {
+        s.lines().map(|(i, l)| f(&self.fields(l, i)?)).collect()
+    }
+}
+
+//the captures of a line matching a pattern, converted with errors at their own column
+pub struct Fields<'a> {
+    caps: Captures<'a>,
+    line: usize,
+}
+
+impl<'a> Fields<'a> {
+    pub fn str(&self, i: usize) -> &'a str {
+        self.caps.get(i).map_or("", |m| m.as_str())
+    }
+
+    pub fn get<T>(&self, i: usize) -> Result<T, SolveError>
+    where
+        T: FromStr,
+        <T as FromStr>::Err: Display,
+    {
+        let s = self.str(i);
+        s.parse()
+            .map_err(|e| self.err(i, format!("Could not parse `{}`: {}", s, e)))
+    }
+
+    //an error pointing at the i-th capture
+    pub fn err(&self, i: usize, msg: impl Into<String>) -> SolveError {
+        let start = self.caps.get(i).map_or(0, |m| m.start());
+        let col = self.caps[0][..start].chars().count() + 1;
+        SolveError::parse(msg).at(self.line + 1, col)
+    }
+}
+
+//Functions
+
+//line endings as \n, without trailing blank lines or newlines
+pub fn normalise(input: &str) -> String {
+    input.replace("\r\n", "\n").trim_end().to_string()
+}
+
+//1-based column of a slice taken from a line
+fn col_of(l: &str, sub: &str) -> usize {
+    let start = sub.as_ptr() as usize - l.as_ptr() as usize;
+    l[..start].chars().count() + 1
+}
+
+//the blocks of the input separated by blank lines
+pub fn sections(input: &str) -> Vec<Section<'_>> {
+    let mut res = vec![];
+    let mut start = None;
+    let mut offset = 0;
+
+    for (i, l) in input.split('\n').enumerate() {
+        match (start, l.trim().is_empty()) {
+            (None, false) => start = Some((i, offset)),
+            (Some((line, from)), true) => {
+                res.push(Section {
+                    text: input[from..offset - 1].trim_end_matches('\r'),
+                    start: line,
+                });
+                start = None;
+            }
+            _ => (),
+        }
+        offset += l.len() + 1;
+    }
+
+    if let Some((line, from)) = start {
+        res.push(Section {
+            text: input[from..].trim_end(),
+            start: line,
+        });
+    }
+
+    res
+}
+
+//exactly n sections, described by what for the error if there aren't
+pub fn split_sections<'a, const N: usize>(
+    input: &'a str,
+    what: &str,
+) -> Result<[Section<'a>; N], SolveError> {
+    let ss = sections(input);
+    let found = ss.len();
+
+    ss.try_into().map_err(|_| {
+        SolveError::parse(format!(
+            "Expected {} separated by a blank line, found {} section{}",
+            what,
+            found,
+            if found == 1 { "" } else { "s" }
+        ))
+    })
+}
+
+//values separated by sep on the given (0-indexed) line, e.g. `3,4,3,1,2`
+pub fn list<T>(l: &str, sep: &str, line: usize) -> Result<Vec<T>, SolveError>
+where
+    T: FromStr,
+    <T as FromStr>::Err: Display,
+{
+    l.split(sep).map(|s| value(l, s, line)).collect()
+}
+
+//values separated by any amount of whitespace on the given (0-indexed) line, e.g. `22 13  0`
+pub fn words<T>(l: &str, line: usize) -> Result<Vec<T>, SolveError>
+where
+    T: FromStr,
+    <T as FromStr>::Err: Display,
+{
+    l.split_whitespace().map(|s| value(l, s, line)).collect()
+}
+
+//parse part of a line, pointing at it if it doesn't parse
+fn value<T>(l: &str, s: &str, line: usize) -> Result<T, SolveError>
+where
+    T: FromStr,
+    <T as FromStr>::Err: Display,
+{
+    let trimmed = s.trim();
+    trimmed.parse().map_err(|e| {
+        SolveError::parse(format!("Could not parse `{}`: {}", trimmed, e))
+            .at(line + 1, col_of(l, trimmed))
+    })
+}
+
+//the (width, height) of a rectangle of characters, rejecting ragged rows
+fn rectangle(s: &Section) -> Result<Pt2d, SolveError> {
+    let width = s.text.lines().next().map_or(0, |l| l.chars().count());
+
+    for (i, l) in s.lines() {
+        let w = l.chars().count();
+        if w != width {
+            return Err(SolveError::parse(format!(
+                "Row is {} characters wide, expected {}",
+                w, width
+            ))
+            .at(i + 1, w.min(width) + 1));
+        }
+    }
+
+    Ok((width as i32, s.text.lines().count() as i32))
+}
+
+//a rectangular grid of characters and its (width, height), rejecting unexpected characters and ragged rows
+pub fn grid<V: PartialEq + Copy>(
+    s: &Section,
+    def: V,
+    p: fn(char) -> Option<V>,
+) -> Result<(Grid<Pt2d, V>, Pt2d), SolveError> {
+    let size = rectangle(s)?;
+    let g = Grid::parse(s.text, def, p).map_err(|e| s.lift(e))?;
+    Ok((g, size))
+}
+
+//like grid, but stored densely
+pub fn dense_grid<V: PartialEq + Copy>(
+    s: &Section,
+    def: V,
+    p: fn(char) -> Option<V>,
+) -> Result<(DenseGrid<V>, Pt2d), SolveError> {
+    let size = rectangle(s)?;
+    let g = DenseGrid::parse(s.text, def, p).map_err(|e| s.lift(e))?;
+    Ok((g, size))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn place(e: SolveError) -> (Option<usize>, Option<usize>) {
+        (e.line, e.col)
+    }
+
+    #[test]
+    fn section_lines() {
+        let input = "a\nb\n\n\nc\r\n\r\nd\ne\n";
+        let ss = sections(input);
+        assert_eq!(
+            ss.iter().map(|s| (s.text, s.start)).collect::<Vec<_>>(),
+            vec![("a\nb", 0), ("c", 4), ("d\ne", 6)]
+        );
+        assert_eq!(ss[2].lines().collect::<Vec<_>>(), vec![(6, "d"), (7, "e")]);
+
+        //an error on the second line of the section is on the eighth of the input
+        let e = SolveError::parse("bad").at(2, 3);
+        assert_eq!(place(ss[2].lift(e)), (Some(8), Some(3)));
+        assert!(split_sections::<2>(input, "two things").is_err());
+    }
+
+    #[test]
+    fn columns() {
+        assert_eq!(list::<u8>("3,4,3,1,2", ",", 0), Ok(vec![3, 4, 3, 1, 2]));
+        assert_eq!(
+            place(list::<u8>("3, 4,x", ",", 4).unwrap_err()),
+            (Some(5), Some(6))
+        );
+        assert_eq!(words::<i32>("22 13  0", 0), Ok(vec![22, 13, 0]));
+        assert_eq!(
+            place(words::<i32>("22 13  z", 1).unwrap_err()),
+            (Some(2), Some(8))
+        );
+
+        let p = Pattern::new(r"(\w+) -> (\d+)", "<name> -> <n>");
+        let f = p.fields("ab -> 12", 2).unwrap();
+        assert_eq!((f.str(1), f.get::<u32>(2)), ("ab", Ok(12)));
+        assert_eq!(place(f.get::<u8>(1).unwrap_err()), (Some(3), Some(1)));
+        assert_eq!(place(f.err(2, "too big")), (Some(3), Some(7)));
+        assert_eq!(
+            place(p.fields("ab => 12", 2).err().unwrap()),
+            (Some(3), Some(1))
+        );
+    }
+
+    #[test]
+    fn ragged() {
+        let ss = sections("x\n\n###\n##\n###");
+        let e = grid(&ss[1], false, |c| Some(c == '#')).err().unwrap();
+        assert_eq!(place(e.clone()), (Some(4), Some(3)));
+        assert_eq!(e.msg, "Row is 2 characters wide, expected 3");
+        assert_eq!(
+            place(
+                dense_grid(&Section::whole("##\n###"), false, |c| Some(c == '#'))
+                    .err()
+                    .unwrap()
+            ),
+            (Some(2), Some(3))
+        );
+
+        let (_, size) = grid(&Section::whole("###\n###"), false, |c| Some(c == '#')).unwrap();
+        assert_eq!(size, (3, 2));
+    }
+
+    #[test]
+    fn normalising() {
+        assert_eq!(normalise("a\r\nb\r\n\r\n"), "a\nb");
+        assert_eq!(normalise("a\n\nb\n\n\n"), "a\n\nb");
+        assert_eq!(normalise("a"), "a");
+    }
+}
@@ -0,0 +1,209 @@
+use super::{DenseGrid, Grid, Point, Pt2d};
+use std::cmp::Reverse;
+use std::collections::{BinaryHeap, HashMap, VecDeque};
+use std::hash::Hash;
+
+//Traits
+
+//grids a search can walk: points outside the grid can't be entered
+pub trait Cells<K, V> {
+    fn cell(&self, k: &K) -> Option<V>;
+}
+
+impl<K: Point + Eq + Hash + Copy, V: PartialEq + Copy> Cells<K, V> for Grid<K, V> {
+    fn cell(&self, k: &K) -> Option<V> {
+        self.grid.get(k).copied()
+    }
+}
+
+impl<V: PartialEq + Copy> Cells<Pt2d, V> for DenseGrid<V> {
+    fn cell(&self, k: &Pt2d) -> Option<V> {
+        self.get(k)
+    }
+}
+
+//Structs
+
+//the cost of a route and every point along it, start and goal included
+#[derive(Debug, Clone, PartialEq)]
+pub struct Path<K> {
+    pub cost: usize,
+    pub steps: Vec<K>,
+}
+
+impl<K: Point + Eq + Hash + Copy> Path<K> {
+    //the steps as a grid of true, e.g. to show with print_2d
+    pub fn to_grid(&self) -> Grid<K, bool> {
+        let start = self.steps[0];
+        Grid::new(
+            self.steps.iter().map(|k| (*k, true)).collect(),
+            start,
+            false,
+        )
+    }
+}
+
+//Functions
+
+//walk back from the goal through where each point was reached from
+fn trace<K: Eq + Hash + Copy>(from: &HashMap<K, K>, start: K, goal: K, cost: usize) -> Path<K> {
+    let mut steps = vec![goal];
+    while steps[steps.len() - 1] != start {
+        steps.push(from[&steps[steps.len() - 1]]);
+    }
+    steps.reverse();
+    Path { cost, steps }
+}
+
+//fewest steps from start to goal, moving between cardinal neighbours where passable(from, to) allows
+pub fn bfs<K, V: Copy>(
+    g: &impl Cells<K, V>,
+    start: K,
+    goal: K,
+    passable: impl Fn(V, V) -> bool,
+) -> Option<Path<K>>
+where
+    K: Point + Eq + Hash + Copy,
+{
+    let mut from: HashMap<K, K> = HashMap::new();
+    let mut search = VecDeque::from([(start, 0)]);
+
+    while let Some((k, n)) = search.pop_front() {
+        if k == goal {
+            return Some(trace(&from, start, goal, n));
+        }
+
+        let v = g.cell(&k)?;
+        for next in k.neighbours_card() {
+            if next == start || from.contains_key(&next) {
+                continue;
+            }
+            if g.cell(&next).is_some_and(|nv| passable(v, nv)) {
+                from.insert(next, k);
+                search.push_back((next, n + 1));
+            }
+        }
+    }
+
+    None
+}
+
+//cheapest route from start to goal, where stepping between cardinal neighbours costs cost(from, to)
+pub fn dijkstra<K, V: Copy>(
+    g: &impl Cells<K, V>,
+    start: K,
+    goal: K,
+    cost: impl Fn(V, V) -> usize,
+    passable: impl Fn(V, V) -> bool,
+) -> Option<Path<K>>
+where
+    K: Point + Eq + Hash + Copy + Ord,
+{
+    search(g, start, goal, cost, passable, |_| 0)
+}
+
+//like dijkstra, but searching towards the goal first. the manhattan distance guides it, so every step must cost at least 1
+pub fn astar<K, V: Copy>(
+    g: &impl Cells<K, V>,
+    start: K,
+    goal: K,
+    cost: impl Fn(V, V) -> usize,
+    passable: impl Fn(V, V) -> bool,
+) -> Option<Path<K>>
+where
+    K: Point + Eq + Hash + Copy + Ord,
+{
+    search(g, start, goal, cost, passable, |k| {
+        goal.sub(k).mag() as usize
+    })
+}
+
+//best-first search ordered by cost so far plus the heuristic, settling each point once
+fn search<K, V: Copy>(
+    g: &impl Cells<K, V>,
+    start: K,
+    goal: K,
+    cost: impl Fn(V, V) -> usize,
+    passable: impl Fn(V, V) -> bool,
+    heuristic: impl Fn(&K) -> usize,
+) -> Option<Path<K>>
+where
+    K: Point + Eq + Hash + Copy + Ord,
+{
+    let mut best: HashMap<K, usize> = HashMap::from([(start, 0)]);
+    let mut from: HashMap<K, K> = HashMap::new();
+    let mut queue = BinaryHeap::from([Reverse((heuristic(&start), start))]);
+
+    while let Some(Reverse((p, k))) = queue.pop() {
+        //skip points already settled more cheaply since this was queued
+        let c = best[&k];
+        if p > c + heuristic(&k) {
+            continue;
+        }
+        if k == goal {
+            return Some(trace(&from, start, goal, c));
+        }
+
+        let v = g.cell(&k)?;
+        for next in k.neighbours_card() {
+            let Some(nv) = g.cell(&next).filter(|nv| passable(v, *nv)) else {
+                continue;
+            };
+            let nc = c + cost(v, nv);
+            if best.get(&next).is_some_and(|b| *b <= nc) {
+                continue;
+            }
+            best.insert(next, nc);
+            from.insert(next, k);
+            queue.push(Reverse((nc + heuristic(&next), next)));
+        }
+    }
+
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MAZE: &str = "#.###
+#...#
+###.#
+#...#
+#.###";
+
+    fn maze() -> Grid<Pt2d, bool> {
+        Grid::from(MAZE.to_string(), false, |c| c == '.')
+    }
+
+    #[test]
+    fn bfs_path() {
+        let p = bfs(&maze(), (1, 0), (1, 4), |_, to| to).unwrap();
+        assert_eq!(p.cost, 8);
+        assert_eq!(p.steps.len(), 9);
+        assert_eq!(
+            p.to_grid().print_2d(|b| if b { '#' } else { '.' }),
+            "\n.#..\n.###\n...#\n.###\n.#..\n"
+        );
+    }
+
+    #[test]
+    fn costs_agree() {
+        let g = Grid::from(String::from("19111\n11191\n99991\n11111"), 0, |c| {
+            c.to_digit(10).unwrap() as usize
+        });
+        let cost = |_, to| to;
+        let d = dijkstra(&g, (0, 0), (0, 3), cost, |_, _| true).unwrap();
+        let a = astar(&g, (0, 0), (0, 3), cost, |_, _| true).unwrap();
+        assert_eq!(
+            (d.cost, d.steps),
+            (11, vec![(0, 0), (0, 1), (0, 2), (0, 3)])
+        );
+        assert_eq!(a.cost, 11);
+
+        //treating 9s as walls forces the long way round, and walls in the 9 at the top
+        let around = astar(&g, (0, 0), (0, 3), cost, |_, to| to < 9).unwrap();
+        assert_eq!((around.cost, around.steps.len()), (13, 14));
+        assert_eq!(dijkstra(&g, (0, 0), (1, 0), cost, |_, to| to < 9), None);
+    }
+}